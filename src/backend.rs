@@ -0,0 +1,119 @@
+//! The halo2 fork the chips and circuits are built against, selected with the
+//! `backend-pse` (default) or `backend-zcash` feature.
+//!
+//! Both forks export the same `circuit`, `plonk`, `poly` and `dev` modules, but
+//! have different field traits and only PSE names its lookups. `Field` and
+//! `lookup` paper over the difference, so chips import everything from here.
+
+#[cfg(all(feature = "backend-zcash", feature = "backend-pse"))]
+compile_error!("the `backend-zcash` and `backend-pse` features are mutually exclusive");
+
+#[cfg(not(any(feature = "backend-zcash", feature = "backend-pse")))]
+compile_error!("select a backend with the `backend-zcash` or `backend-pse` feature");
+
+#[cfg(feature = "backend-pse")]
+pub use halo2_proofs as halo2;
+#[cfg(feature = "backend-zcash")]
+pub use halo2_proofs_zcash as halo2;
+
+pub use halo2::{circuit, dev, plonk, poly};
+
+#[cfg(feature = "backend-zcash")]
+pub use ff;
+/// The `ff` version the backend's fields implement.
+#[cfg(feature = "backend-pse")]
+pub use halo2::halo2curves::ff;
+
+use plonk::{ConstraintSystem, Expression, TableColumn, VirtualCells};
+
+/// The field of the backend's curves, which the tests and the command line
+/// use: the base field of Pallas on zcash and the scalar field of bn256 on PSE.
+#[cfg(feature = "backend-pse")]
+pub use halo2::halo2curves::bn256::Fr as Fp;
+#[cfg(feature = "backend-zcash")]
+pub use halo2::pasta::Fp;
+
+/// The field trait of the backend, `FieldExt + PrimeFieldBits` on zcash.
+#[cfg(feature = "backend-zcash")]
+pub trait Field: halo2::arithmetic::FieldExt + ff::PrimeFieldBits {
+    /// The little-endian bits of the canonical representation.
+    fn le_bits(&self) -> Vec<bool> {
+        self.to_le_bits().iter().by_vals().collect()
+    }
+}
+
+#[cfg(feature = "backend-zcash")]
+impl<F: halo2::arithmetic::FieldExt + ff::PrimeFieldBits> Field for F {}
+
+/// The field trait of the backend, `PrimeField` on PSE. ff 0.13 replaced
+/// `zero()` and `one()` by constants, they are kept here for the chips.
+#[cfg(feature = "backend-pse")]
+pub trait Field: halo2::halo2curves::ff::PrimeField {
+    fn zero() -> Self {
+        Self::ZERO
+    }
+
+    fn one() -> Self {
+        Self::ONE
+    }
+
+    /// The little-endian bits of the canonical representation, assuming a
+    /// little-endian `Repr` as for bn256 and Pasta.
+    fn le_bits(&self) -> Vec<bool> {
+        self.to_repr()
+            .as_ref()
+            .iter()
+            .flat_map(|byte| (0..8).map(move |i| (byte >> i) & 1 == 1))
+            .collect()
+    }
+}
+
+#[cfg(feature = "backend-pse")]
+impl<F: halo2::halo2curves::ff::PrimeField> Field for F {}
+
+/// `meta.lookup`, with the name PSE asks for.
+#[cfg(feature = "backend-pse")]
+pub fn lookup<F: Field>(
+    meta: &mut ConstraintSystem<F>,
+    name: &'static str,
+    table_map: impl FnOnce(&mut VirtualCells<'_, F>) -> Vec<(Expression<F>, TableColumn)>,
+) -> usize {
+    meta.lookup(name, table_map)
+}
+
+/// `meta.lookup`, the name is only used by the PSE backend.
+#[cfg(feature = "backend-zcash")]
+pub fn lookup<F: Field>(
+    meta: &mut ConstraintSystem<F>,
+    _name: &'static str,
+    table_map: impl FnOnce(&mut VirtualCells<'_, F>) -> Vec<(Expression<F>, TableColumn)>,
+) -> usize {
+    meta.lookup(table_map)
+}
+
+/// The column type of an advice cell in a `VerifyFailure`, which PSE tags with
+/// the phase of the column.
+#[cfg(all(test, feature = "backend-pse"))]
+pub(crate) fn any_advice() -> plonk::Any {
+    plonk::Any::advice()
+}
+
+#[cfg(all(test, feature = "backend-zcash"))]
+pub(crate) fn any_advice() -> plonk::Any {
+    plonk::Any::Advice
+}
+
+/// The bound PSE puts on the `Assignment` a floor planner synthesizes into,
+/// which the zcash fork does not have.
+#[cfg(all(test, feature = "backend-pse"))]
+pub(crate) use plonk::SyncDeps;
+
+/// Whether `failure` is the first lookup failing at `location`, ignoring the
+/// name PSE also reports.
+#[cfg(test)]
+pub(crate) fn is_lookup_failure(failure: &dev::VerifyFailure, at: &dev::FailureLocation) -> bool {
+    matches!(
+        failure,
+        dev::VerifyFailure::Lookup { lookup_index: 0, location, .. } if location == at
+    )
+}
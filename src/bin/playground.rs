@@ -0,0 +1,744 @@
+//! Run the playground circuits from the command line, e.g.
+//!
+//!     cargo run --bin playground -- mock fib-1 --public 1,1,55 --private 10
+//!     cargo run --bin playground -- setup range-check-4 --num-bits 64
+//!     cargo run --bin playground -- prove range-check-4 --num-bits 64 --private 123456
+//!     cargo run --bin playground -- verify range-check-4 --num-bits 64
+//!
+//! The circuits are proven with KZG on the PSE backend (default), or with IPA
+//! when built with `--no-default-features --features backend-zcash`.
+
+use std::{
+    error::Error,
+    fs::File,
+    io::{BufReader, BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use serde::Deserialize;
+
+use halo2_examples::{
+    backend::{
+        circuit::Value,
+        dev::{CircuitCost, MockProver},
+        plonk::{Assigned, Circuit, ConstraintSystem},
+        Fp,
+    },
+    circuits::{
+        fib_1::FibonacciCircuit1, fib_2::FibonacciCircuit2, fib_3::FibonacciCircuit3,
+        range_check_1::RangeCheckCircuit1, range_check_2::RangeCheckCircuit2,
+        range_check_3::RangeCheckCircuit3, range_check_4::RangeCheckDecomposeCircuit,
+    },
+    proving::serialization::{self, Backend, Header},
+};
+
+#[cfg(feature = "backend-zcash")]
+use halo2_examples::proving::ipa;
+#[cfg(feature = "backend-pse")]
+use halo2_examples::proving::kzg;
+
+/// The curve whose proofs `stats` measures.
+#[cfg(feature = "backend-pse")]
+type Curve = halo2_proofs::halo2curves::bn256::G1;
+#[cfg(feature = "backend-zcash")]
+type Curve = halo2_proofs_zcash::pasta::Eq;
+
+// Same parameters as the tests of each range check circuit
+const RANGE_1: usize = 8; // 3-bit value
+const RANGE_2: usize = 8; // 3-bit value
+const LOOKUP_RANGE_2: usize = 256; // 8-bit value
+const LOOKUP_RANGE_3: usize = 256; // 8-bit value
+const NUM_BITS_3: usize = 8;
+const LOOKUP_NUM_BITS_4: usize = 10;
+const LOOKUP_RANGE_4: usize = 1024; // 10-bit value
+const DEFAULT_NUM_BITS_4: usize = 64;
+
+type CliResult = Result<(), Box<dyn Error>>;
+
+#[cfg(feature = "backend-pse")]
+const BACKEND: Backend = Backend::Kzg;
+#[cfg(feature = "backend-zcash")]
+const BACKEND: Backend = Backend::Ipa;
+
+#[derive(Parser)]
+#[command(about = "Run the halo2 playground circuits")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Check the inputs against the circuit with MockProver
+    Mock(CircuitArgs),
+    /// Generate params and the verifying key into --dir
+    Setup(CircuitArgs),
+    /// Create a proof from the params in --dir
+    Prove {
+        #[command(flatten)]
+        args: CircuitArgs,
+        #[cfg(feature = "backend-pse")]
+        #[command(flatten)]
+        kzg: KzgArgs,
+    },
+    /// Verify the proof and public inputs stored in --dir
+    Verify {
+        #[command(flatten)]
+        args: CircuitArgs,
+        #[cfg(feature = "backend-pse")]
+        #[command(flatten)]
+        kzg: KzgArgs,
+    },
+    /// Render the circuit layout to a PNG file
+    Layout {
+        #[command(flatten)]
+        args: CircuitArgs,
+        /// Defaults to `<dir>/<circuit>-layout.png`
+        #[arg(long)]
+        output: Option<PathBuf>,
+    },
+    /// Print the size of the constraint system and of its proofs
+    Stats(CircuitArgs),
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum CircuitName {
+    #[value(name = "fib-1")]
+    Fib1,
+    #[value(name = "fib-2")]
+    Fib2,
+    #[value(name = "fib-3")]
+    Fib3,
+    #[value(name = "range-check-1")]
+    RangeCheck1,
+    #[value(name = "range-check-2")]
+    RangeCheck2,
+    #[value(name = "range-check-3")]
+    RangeCheck3,
+    #[value(name = "range-check-4")]
+    RangeCheck4,
+}
+
+/// Inputs per circuit:
+///
+/// - fib-1, fib-2, fib-3: public = [F(1), F(2), F(n)], private = [n]
+/// - range-check-1: private = [value]
+/// - range-check-2: private = [value, lookup_value]
+/// - range-check-3: private = [num_bits, value]
+/// - range-check-4: private = [value]
+///
+/// Missing private values of the range checks are left unknown, which is
+/// enough for every subcommand except `mock` and `prove`. The fib circuits need
+/// their public inputs for these two only.
+#[derive(Args)]
+struct CircuitArgs {
+    circuit: CircuitName,
+    /// Defaults to the smallest k the tests use for the circuit
+    #[arg(short, long)]
+    k: Option<u32>,
+    /// Bit width checked by range-check-4, which sets its layout. Part of the
+    /// file names and headers, defaults to 64
+    #[arg(long)]
+    num_bits: Option<usize>,
+    #[arg(long, value_delimiter = ',')]
+    public: Vec<u64>,
+    #[arg(long, value_delimiter = ',')]
+    private: Vec<u64>,
+    /// JSON file of the form `{"public": [..], "private": [..]}`, overriding
+    /// --public and --private
+    #[arg(long)]
+    inputs: Option<PathBuf>,
+    /// Directory holding params, verifying key, proof and public inputs
+    #[arg(long, default_value = ".")]
+    dir: PathBuf,
+}
+
+#[cfg(feature = "backend-pse")]
+#[derive(Args)]
+struct KzgArgs {
+    #[arg(long, value_enum, default_value_t = MultiOpen::Shplonk)]
+    multiopen: MultiOpen,
+    #[arg(long, value_enum, default_value_t = TranscriptHash::Blake2b)]
+    hash: TranscriptHash,
+}
+
+#[cfg(feature = "backend-pse")]
+#[derive(Clone, Copy, ValueEnum)]
+enum MultiOpen {
+    Shplonk,
+    Gwc,
+}
+
+#[cfg(feature = "backend-pse")]
+#[derive(Clone, Copy, ValueEnum)]
+enum TranscriptHash {
+    Blake2b,
+    Keccak256,
+}
+
+#[cfg(feature = "backend-pse")]
+impl KzgArgs {
+    fn multiopen(&self) -> kzg::MultiOpen {
+        match self.multiopen {
+            MultiOpen::Shplonk => kzg::MultiOpen::Shplonk,
+            MultiOpen::Gwc => kzg::MultiOpen::Gwc,
+        }
+    }
+
+    fn hash(&self) -> kzg::TranscriptHash {
+        match self.hash {
+            TranscriptHash::Blake2b => kzg::TranscriptHash::Blake2b,
+            TranscriptHash::Keccak256 => kzg::TranscriptHash::Keccak256,
+        }
+    }
+}
+
+#[derive(Default, Deserialize)]
+struct Inputs {
+    #[serde(default)]
+    public: Vec<u64>,
+    #[serde(default)]
+    private: Vec<u64>,
+}
+
+/// What every subcommand needs to know about the selected circuit.
+struct Spec {
+    name: String,
+    k: u32,
+    dir: PathBuf,
+}
+
+impl Spec {
+    fn header(&self) -> Header {
+        Header::new(&self.name, self.k, BACKEND)
+    }
+
+    fn path(&self, extension: &str) -> PathBuf {
+        self.dir.join(format!("{}.{}", self.name, extension))
+    }
+}
+
+/// A subcommand, run with the proving system of the backend. `instances` holds
+/// one vector per instance column of the circuit.
+trait Action {
+    /// Whether the action synthesizes the witness, and so needs the inputs.
+    const WITNESS: bool = false;
+
+    fn run<C: Circuit<Fp>>(&self, spec: &Spec, circuit: C, instances: Vec<Vec<Fp>>) -> CliResult;
+}
+
+fn run<A: Action>(args: &CircuitArgs, action: &A) -> CliResult {
+    let inputs = match &args.inputs {
+        Some(path) => serde_json::from_reader(BufReader::new(File::open(path)?))?,
+        None => Inputs {
+            public: args.public.clone(),
+            private: args.private.clone(),
+        },
+    };
+    let spec = |name: &str, default_k| Spec {
+        name: name.to_string(),
+        k: args.k.unwrap_or(default_k),
+        dir: args.dir.clone(),
+    };
+
+    // The inputs each circuit takes, as (public, private) lengths
+    let (num_public, num_private) = match args.circuit {
+        CircuitName::Fib1 | CircuitName::Fib2 | CircuitName::Fib3 => (3, 1),
+        CircuitName::RangeCheck1 | CircuitName::RangeCheck4 => (0, 1),
+        CircuitName::RangeCheck2 | CircuitName::RangeCheck3 => (0, 2),
+    };
+    if num_public == 0 && !inputs.public.is_empty() {
+        return Err("range check circuits have no instance column, drop --public".into());
+    }
+    if inputs.public.len() != num_public && (A::WITNESS || !inputs.public.is_empty()) {
+        return Err(format!(
+            "expected {} public inputs, got {}",
+            num_public,
+            inputs.public.len()
+        )
+        .into());
+    }
+    if inputs.private.len() > num_private {
+        return Err(format!(
+            "expected at most {} private inputs, got {}",
+            num_private,
+            inputs.private.len()
+        )
+        .into());
+    }
+    if args.num_bits.is_some() && !matches!(args.circuit, CircuitName::RangeCheck4) {
+        return Err("--num-bits only applies to range-check-4".into());
+    }
+
+    let public: Vec<Fp> = inputs.public.iter().map(|&v| Fp::from(v)).collect();
+    let private = |i: usize| inputs.private.get(i).copied();
+    let witness = |i: usize| match private(i) {
+        Some(v) => Value::known(Assigned::from(Fp::from(v))),
+        None => Value::unknown(),
+    };
+    let fib_n = || match private(0) {
+        Some(n) if n >= 3 => Ok(n as usize),
+        _ => Err("fib circuits need the index n >= 3 as the first private input"),
+    };
+
+    match args.circuit {
+        CircuitName::Fib1 => {
+            let circuit = FibonacciCircuit1::<Fp>::new(fib_n()?);
+            let spec = spec(FibonacciCircuit1::<Fp>::NAME, circuit.k());
+            action.run(&spec, circuit, vec![public])
+        }
+        CircuitName::Fib2 => {
+            let circuit = FibonacciCircuit2::<Fp>::new(fib_n()?);
+            let spec = spec(FibonacciCircuit2::<Fp>::NAME, circuit.k());
+            action.run(&spec, circuit, vec![public])
+        }
+        CircuitName::Fib3 => {
+            let circuit = FibonacciCircuit3::<Fp>::new(fib_n()?);
+            let spec = spec(FibonacciCircuit3::<Fp>::NAME, circuit.k());
+            action.run(&spec, circuit, vec![public])
+        }
+        CircuitName::RangeCheck1 => {
+            let circuit = RangeCheckCircuit1::<Fp, RANGE_1> { value: witness(0) };
+            let name = RangeCheckCircuit1::<Fp, RANGE_1>::NAME;
+            action.run(&spec(name, 4), circuit, vec![])
+        }
+        CircuitName::RangeCheck2 => {
+            let circuit = RangeCheckCircuit2::<Fp, RANGE_2, LOOKUP_RANGE_2> {
+                value: witness(0),
+                lookup_value: witness(1),
+            };
+            let name = RangeCheckCircuit2::<Fp, RANGE_2, LOOKUP_RANGE_2>::NAME;
+            action.run(&spec(name, 9), circuit, vec![])
+        }
+        CircuitName::RangeCheck3 => {
+            let num_bits = match private(0) {
+                Some(v) => Value::known(u8::try_from(v)?),
+                None => Value::unknown(),
+            };
+            let circuit = RangeCheckCircuit3::<Fp, LOOKUP_RANGE_3, NUM_BITS_3> {
+                num_bits,
+                value: witness(1),
+            };
+            let name = RangeCheckCircuit3::<Fp, LOOKUP_RANGE_3, NUM_BITS_3>::NAME;
+            action.run(&spec(name, 9), circuit, vec![])
+        }
+        CircuitName::RangeCheck4 => {
+            let num_bits = args.num_bits.unwrap_or(DEFAULT_NUM_BITS_4);
+            let circuit = RangeCheckDecomposeCircuit::<Fp, LOOKUP_NUM_BITS_4, LOOKUP_RANGE_4> {
+                value: witness(0),
+                num_bits,
+            };
+            // keys for one bit width do not fit another
+            let name = format!(
+                "{}-{}-bits",
+                RangeCheckDecomposeCircuit::<Fp, LOOKUP_NUM_BITS_4, LOOKUP_RANGE_4>::NAME,
+                num_bits
+            );
+            action.run(&spec(&name, 11), circuit, vec![])
+        }
+    }
+}
+
+fn write_file(
+    path: &Path,
+    write: impl FnOnce(&mut BufWriter<File>) -> std::io::Result<()>,
+) -> CliResult {
+    let mut writer = BufWriter::new(File::create(path)?);
+    write(&mut writer)?;
+    writer.flush()?;
+    println!("wrote {}", path.display());
+    Ok(())
+}
+
+fn read_file<T>(
+    path: &Path,
+    expected: &Header,
+    read: impl FnOnce(&mut BufReader<File>) -> std::io::Result<(Header, T)>,
+) -> Result<T, Box<dyn Error>> {
+    let (header, value) = read(&mut BufReader::new(File::open(path)?))?;
+    header.expect(expected)?;
+    Ok(value)
+}
+
+fn columns(instances: &[Vec<Fp>]) -> Vec<&[Fp]> {
+    instances.iter().map(|column| &column[..]).collect()
+}
+
+struct Mock;
+
+impl Action for Mock {
+    const WITNESS: bool = true;
+
+    fn run<C: Circuit<Fp>>(&self, spec: &Spec, circuit: C, instances: Vec<Vec<Fp>>) -> CliResult {
+        let prover = MockProver::run(spec.k, &circuit, instances)?;
+        match prover.verify() {
+            Ok(()) => {
+                println!("{}: satisfied", spec.name);
+                Ok(())
+            }
+            Err(failures) => {
+                for failure in failures.iter() {
+                    println!("{}", failure);
+                }
+                Err(format!("{}: {} constraint(s) failed", spec.name, failures.len()).into())
+            }
+        }
+    }
+}
+
+struct Setup;
+
+#[cfg(feature = "backend-pse")]
+impl Action for Setup {
+    fn run<C: Circuit<Fp>>(&self, spec: &Spec, circuit: C, _: Vec<Vec<Fp>>) -> CliResult {
+        let header = spec.header();
+        let params = kzg::setup(spec.k);
+        let pk = kzg::keygen(&params, &circuit)?;
+
+        write_file(&spec.path("params"), |w| {
+            serialization::write_params_kzg(w, &header, &params)
+        })?;
+        write_file(&spec.path("vk"), |w| {
+            serialization::write_vk_kzg(w, &header, pk.get_vk())
+        })
+    }
+}
+
+#[cfg(feature = "backend-zcash")]
+impl Action for Setup {
+    fn run<C: Circuit<Fp>>(&self, spec: &Spec, circuit: C, _: Vec<Vec<Fp>>) -> CliResult {
+        let header = spec.header();
+        let params = ipa::setup(spec.k);
+        let pk = ipa::keygen(&params, &circuit)?;
+
+        write_file(&spec.path("params"), |w| {
+            serialization::write_params_ipa(w, &header, &params)
+        })?;
+        write_file(&spec.path("vk"), |w| {
+            serialization::write_vk_ipa(w, &header, pk.get_vk())
+        })
+    }
+}
+
+#[cfg(feature = "backend-pse")]
+struct Prove<'a>(&'a KzgArgs);
+
+#[cfg(feature = "backend-pse")]
+impl Action for Prove<'_> {
+    const WITNESS: bool = true;
+
+    fn run<C: Circuit<Fp>>(&self, spec: &Spec, circuit: C, instances: Vec<Vec<Fp>>) -> CliResult {
+        let header = spec.header();
+        let params = read_file(
+            &spec.path("params"),
+            &header,
+            serialization::read_params_kzg,
+        )?;
+        let pk = kzg::keygen(&params, &circuit)?;
+        let instances = columns(&instances);
+        let proof = kzg::prove(
+            &params,
+            &pk,
+            circuit,
+            &instances,
+            self.0.multiopen(),
+            self.0.hash(),
+        )?;
+
+        write_file(&spec.path("proof"), |w| {
+            serialization::write_proof(w, &header, &proof)
+        })?;
+        write_file(&spec.path("instances"), |w| {
+            serialization::write_instances_kzg(w, &header, &instances)
+        })
+    }
+}
+
+#[cfg(feature = "backend-zcash")]
+struct Prove;
+
+#[cfg(feature = "backend-zcash")]
+impl Action for Prove {
+    const WITNESS: bool = true;
+
+    fn run<C: Circuit<Fp>>(&self, spec: &Spec, circuit: C, instances: Vec<Vec<Fp>>) -> CliResult {
+        let header = spec.header();
+        let params = read_file(
+            &spec.path("params"),
+            &header,
+            serialization::read_params_ipa,
+        )?;
+        let pk = ipa::keygen(&params, &circuit)?;
+        let instances = columns(&instances);
+        let proof = ipa::prove(&params, &pk, circuit, &instances)?;
+
+        write_file(&spec.path("proof"), |w| {
+            serialization::write_proof(w, &header, &proof)
+        })?;
+        write_file(&spec.path("instances"), |w| {
+            serialization::write_instances_ipa(w, &header, &instances)
+        })
+    }
+}
+
+#[cfg(feature = "backend-pse")]
+struct Verify<'a>(&'a KzgArgs);
+
+#[cfg(feature = "backend-pse")]
+impl Action for Verify<'_> {
+    fn run<C: Circuit<Fp>>(&self, spec: &Spec, _: C, _: Vec<Vec<Fp>>) -> CliResult {
+        let header = spec.header();
+        let params = read_file(
+            &spec.path("params"),
+            &header,
+            serialization::read_params_kzg,
+        )?;
+        let vk = read_file(
+            &spec.path("vk"),
+            &header,
+            serialization::read_vk_kzg::<_, C>,
+        )?;
+        let proof = read_file(&spec.path("proof"), &header, serialization::read_proof)?;
+        let instances = read_file(
+            &spec.path("instances"),
+            &header,
+            serialization::read_instances_kzg,
+        )?;
+
+        kzg::verify(
+            &params,
+            &vk,
+            &proof,
+            &columns(&instances),
+            self.0.multiopen(),
+            self.0.hash(),
+        )?;
+        println!("{}: proof verified", spec.name);
+        Ok(())
+    }
+}
+
+#[cfg(feature = "backend-zcash")]
+struct Verify;
+
+#[cfg(feature = "backend-zcash")]
+impl Action for Verify {
+    fn run<C: Circuit<Fp>>(&self, spec: &Spec, circuit: C, _: Vec<Vec<Fp>>) -> CliResult {
+        let header = spec.header();
+        let params = read_file(
+            &spec.path("params"),
+            &header,
+            serialization::read_params_ipa,
+        )?;
+        let vk = read_file(&spec.path("vk"), &header, |r| {
+            serialization::read_vk_ipa(r, &params, &circuit)
+        })?;
+        let proof = read_file(&spec.path("proof"), &header, serialization::read_proof)?;
+        let instances = read_file(
+            &spec.path("instances"),
+            &header,
+            serialization::read_instances_ipa,
+        )?;
+
+        ipa::verify(&params, &vk, &proof, &columns(&instances))?;
+        println!("{}: proof verified", spec.name);
+        Ok(())
+    }
+}
+
+// The output path is only read when layouts can be rendered
+#[cfg_attr(not(feature = "dev-graph"), allow(dead_code))]
+struct Layout<'a>(&'a Option<PathBuf>);
+
+impl Action for Layout<'_> {
+    #[cfg(feature = "dev-graph")]
+    fn run<C: Circuit<Fp>>(&self, spec: &Spec, circuit: C, _: Vec<Vec<Fp>>) -> CliResult {
+        use plotters::prelude::*;
+
+        let output = self
+            .0
+            .clone()
+            .unwrap_or_else(|| spec.dir.join(format!("{}-layout.png", spec.name)));
+        let root = BitMapBackend::new(&output, (1024, 3096)).into_drawing_area();
+        root.fill(&WHITE)?;
+        let root = root.titled(&spec.name, ("sans-serif", 60))?;
+
+        halo2_examples::backend::dev::CircuitLayout::default()
+            .render(spec.k, &circuit, &root)
+            .map_err(|e| format!("{:?}", e))?;
+        println!("wrote {}", output.display());
+        Ok(())
+    }
+
+    #[cfg(not(feature = "dev-graph"))]
+    fn run<C: Circuit<Fp>>(&self, _: &Spec, _: C, _: Vec<Vec<Fp>>) -> CliResult {
+        Err("rebuild with --features dev-graph to render layouts".into())
+    }
+}
+
+struct Stats;
+
+impl Action for Stats {
+    fn run<C: Circuit<Fp>>(&self, spec: &Spec, circuit: C, instances: Vec<Vec<Fp>>) -> CliResult {
+        let mut cs = ConstraintSystem::<Fp>::default();
+        C::configure(&mut cs);
+        let cost = CircuitCost::<Curve, C>::measure(spec.k as _, &circuit);
+        let num_instances = instances.iter().map(Vec::len).sum();
+
+        println!("{} ({:?}, k = {})", spec.name, BACKEND, spec.k);
+        println!("degree: {}", cs.degree());
+        println!("blinding factors: {}", cs.blinding_factors());
+        println!("minimum rows: {}", cs.minimum_rows());
+        println!(
+            "proof size: {} bytes",
+            usize::from(cost.proof_size(num_instances))
+        );
+        println!(
+            "marginal proof size: {} bytes",
+            usize::from(cost.marginal_proof_size())
+        );
+        Ok(())
+    }
+}
+
+fn main() -> CliResult {
+    execute(Cli::parse().command)
+}
+
+fn execute(command: Command) -> CliResult {
+    match command {
+        Command::Mock(args) => run(&args, &Mock),
+        Command::Setup(args) => run(&args, &Setup),
+        #[cfg(feature = "backend-pse")]
+        Command::Prove { args, kzg } => run(&args, &Prove(&kzg)),
+        #[cfg(feature = "backend-zcash")]
+        Command::Prove { args } => run(&args, &Prove),
+        #[cfg(feature = "backend-pse")]
+        Command::Verify { args, kzg } => run(&args, &Verify(&kzg)),
+        #[cfg(feature = "backend-zcash")]
+        Command::Verify { args } => run(&args, &Verify),
+        Command::Layout { args, output } => run(&args, &Layout(&output)),
+        Command::Stats(args) => run(&args, &Stats),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use clap::Parser;
+
+    use super::{execute, Cli, CliResult};
+
+    // A fresh directory for the files of one test
+    fn tmp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "halo2-playground-cli-{}-{}",
+            std::process::id(),
+            name
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn playground(dir: &PathBuf, args: &[&str]) -> CliResult {
+        let dir = dir.to_str().unwrap();
+        let args = ["playground"].iter().chain(args).chain(&["--dir", dir]);
+        execute(Cli::try_parse_from(args)?.command)
+    }
+
+    #[test]
+    fn fib_1_round_trip() {
+        let dir = tmp_dir("fib-1");
+
+        playground(&dir, &["setup", "fib-1", "--private", "10"]).unwrap();
+        playground(
+            &dir,
+            &["prove", "fib-1", "--public", "1,1,55", "--private", "10"],
+        )
+        .unwrap();
+        playground(&dir, &["verify", "fib-1", "--private", "10"]).unwrap();
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn range_check_4_round_trip() {
+        let dir = tmp_dir("range-check-4");
+
+        playground(&dir, &["setup", "range-check-4", "--num-bits", "16"]).unwrap();
+        let prove = ["prove", "range-check-4", "--num-bits", "16", "--private"];
+        playground(&dir, &[&prove[..], &["12345"]].concat()).unwrap();
+        playground(&dir, &["verify", "range-check-4", "--num-bits", "16"]).unwrap();
+
+        // files for 16 bits are rejected for 32 bits, even under its file names
+        let verify_32 = ["verify", "range-check-4", "--num-bits", "32"];
+        assert!(playground(&dir, &verify_32).is_err());
+        for extension in ["params", "vk", "proof", "instances"] {
+            std::fs::copy(
+                dir.join(format!("range_check_4-16-bits.{}", extension)),
+                dir.join(format!("range_check_4-32-bits.{}", extension)),
+            )
+            .unwrap();
+        }
+        assert!(playground(&dir, &verify_32).is_err());
+
+        // a 17-bit value does not fit, the prover or the verifier rejects it
+        if playground(&dir, &[&prove[..], &["65536"]].concat()).is_ok() {
+            assert!(playground(&dir, &["verify", "range-check-4", "--num-bits", "16"]).is_err());
+        }
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn mock() {
+        let dir = tmp_dir("mock");
+        let inputs = dir.join("inputs.json");
+        std::fs::write(&inputs, r#"{"public": [1, 1, 55], "private": [10]}"#).unwrap();
+
+        playground(
+            &dir,
+            &["mock", "fib-1", "--inputs", inputs.to_str().unwrap()],
+        )
+        .unwrap();
+        playground(&dir, &["mock", "range-check-3", "--private", "8,255"]).unwrap();
+
+        // F(10) = 55
+        assert!(playground(
+            &dir,
+            &["mock", "fib-1", "--public", "1,1,56", "--private", "10"]
+        )
+        .is_err());
+        // 256 does not fit in 8 bits
+        assert!(playground(&dir, &["mock", "range-check-3", "--private", "8,256"]).is_err());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn invalid_inputs() {
+        let dir = tmp_dir("invalid-inputs");
+
+        for args in [
+            // n >= 3
+            &["mock", "fib-1", "--public", "1,1,1", "--private", "2"][..],
+            // three public inputs
+            &["mock", "fib-2", "--public", "1,1", "--private", "10"],
+            &["setup", "fib-3", "--public", "1,1", "--private", "10"],
+            // no instance column
+            &["mock", "range-check-1", "--public", "1", "--private", "3"],
+            // too many private inputs
+            &["mock", "range-check-4", "--private", "5,8"],
+            // num_bits does not fit in a u8
+            &["mock", "range-check-3", "--private", "256,1"],
+            // range-check-4 only
+            &["setup", "range-check-1", "--num-bits", "8"],
+        ] {
+            assert!(playground(&dir, args).is_err(), "{:?}", args);
+        }
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,294 @@
+use std::marker::PhantomData;
+
+use crate::backend::{
+    circuit::{AssignedCell, Layouter, Region, Value},
+    plonk::{
+        Advice, Assigned, Column, ConstraintSystem, Constraints, Error, Expression, Fixed, Selector,
+    },
+    poly::Rotation,
+    Field,
+};
+use crate::chips::range_check_4::RangeCheckDecomposeConfig;
+use crate::chips::range_constrained::RangeConstrained;
+use crate::chips::table_registry::TableRegistry;
+
+/// Compares two values `a` and `b` that are known to be in [0, 2^N).
+///
+/// `a < b` iff `d = b - a - 1 + 2^N` is at least 2^N. As d is in [0, 2^{N+1}),
+/// its lowest N bits are decomposed with `RangeCheckDecomposeConfig` and the
+/// remaining running sum is its top bit, which is the result of the comparison.
+/// `a <= b` uses `d = b - a + 2^N` in the same way.
+///
+/// The `assert_*` variants skip the boolean and decompose `b - a - 1` (resp.
+/// `b - a`) into N bits, with the final running sum constrained to 0.
+///
+///     | a | b | diff               | bit       | offset      | q_compare | q_bool |
+///     ------------------------------------------------------------------------------
+///     | a | b | b - a + offset     | diff >> N | 2^N - 1     |     1     |   1    |
+///
+/// N must be a multiple of K. The inputs are `RangeConstrained` values, whose
+/// bound must be at most N bits: a wider input could wrap around the modulus in
+/// `b - a`, so it fails with `Error::Synthesis`.
+#[derive(Debug, Clone)]
+pub struct ComparisonConfig<
+    F: Field,
+    const N: usize,
+    const LOOKUP_NUM_BITS: usize,
+    const LOOKUP_RANGE: usize,
+> {
+    a: Column<Advice>,
+    b: Column<Advice>,
+    diff: Column<Advice>,
+    bit: Column<Advice>,
+    offset: Column<Fixed>,
+    q_compare: Selector,
+    q_bool: Selector,
+    pub decompose: RangeCheckDecomposeConfig<F, LOOKUP_NUM_BITS, LOOKUP_RANGE>,
+}
+
+pub struct ComparisonChip<
+    F: Field,
+    const N: usize,
+    const LOOKUP_NUM_BITS: usize,
+    const LOOKUP_RANGE: usize,
+> {
+    pub config: ComparisonConfig<F, N, LOOKUP_NUM_BITS, LOOKUP_RANGE>,
+    pub _marker: PhantomData<F>,
+}
+
+impl<F: Field, const N: usize, const LOOKUP_NUM_BITS: usize, const LOOKUP_RANGE: usize>
+    ComparisonChip<F, N, LOOKUP_NUM_BITS, LOOKUP_RANGE>
+{
+    pub fn construct(config: ComparisonConfig<F, N, LOOKUP_NUM_BITS, LOOKUP_RANGE>) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    /// The `assert_*` variants need a constants column, like range_check_4.
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 4],
+        offset: Column<Fixed>,
+        tables: &mut TableRegistry,
+    ) -> ComparisonConfig<F, N, LOOKUP_NUM_BITS, LOOKUP_RANGE> {
+        assert!(N > 0 && N < 64);
+        assert_eq!(N % LOOKUP_NUM_BITS, 0, "N must be a multiple of K");
+
+        let [a, b, diff, bit] = advice;
+        let q_compare = meta.selector();
+        let q_bool = meta.selector();
+
+        for column in advice {
+            meta.enable_equality(column);
+        }
+
+        meta.create_gate("Comparison", |meta| {
+            let q_compare = meta.query_selector(q_compare);
+            let a = meta.query_advice(a, Rotation::cur());
+            let b = meta.query_advice(b, Rotation::cur());
+            let diff = meta.query_advice(diff, Rotation::cur());
+            let offset = meta.query_fixed(offset, Rotation::cur());
+
+            Constraints::with_selector(q_compare, [("diff", b - a + offset - diff)])
+        });
+
+        meta.create_gate("Top bit", |meta| {
+            let q_bool = meta.query_selector(q_bool);
+            let bit = meta.query_advice(bit, Rotation::cur());
+
+            Constraints::with_selector(
+                q_bool,
+                [(
+                    "boolean",
+                    bit.clone() * (Expression::Constant(F::one()) - bit),
+                )],
+            )
+        });
+
+        let decompose = RangeCheckDecomposeConfig::configure(meta, a, b, tables);
+
+        ComparisonConfig {
+            a,
+            b,
+            diff,
+            bit,
+            offset,
+            q_compare,
+            q_bool,
+            decompose,
+        }
+    }
+
+    /// Returns a boolean cell holding `a < b`.
+    pub fn lt(
+        &self,
+        layouter: impl Layouter<F>,
+        a: &RangeConstrained<F>,
+        b: &RangeConstrained<F>,
+    ) -> Result<AssignedCell<Assigned<F>, F>, Error> {
+        self.compare(layouter, a, b, true)
+    }
+
+    /// Returns a boolean cell holding `a <= b`.
+    pub fn le(
+        &self,
+        layouter: impl Layouter<F>,
+        a: &RangeConstrained<F>,
+        b: &RangeConstrained<F>,
+    ) -> Result<AssignedCell<Assigned<F>, F>, Error> {
+        self.compare(layouter, a, b, false)
+    }
+
+    /// Returns a boolean cell holding `a > b`.
+    pub fn gt(
+        &self,
+        layouter: impl Layouter<F>,
+        a: &RangeConstrained<F>,
+        b: &RangeConstrained<F>,
+    ) -> Result<AssignedCell<Assigned<F>, F>, Error> {
+        self.compare(layouter, b, a, true)
+    }
+
+    /// Returns a boolean cell holding `a >= b`.
+    pub fn ge(
+        &self,
+        layouter: impl Layouter<F>,
+        a: &RangeConstrained<F>,
+        b: &RangeConstrained<F>,
+    ) -> Result<AssignedCell<Assigned<F>, F>, Error> {
+        self.compare(layouter, b, a, false)
+    }
+
+    /// Constrain `a < b`.
+    pub fn assert_lt(
+        &self,
+        layouter: impl Layouter<F>,
+        a: &RangeConstrained<F>,
+        b: &RangeConstrained<F>,
+    ) -> Result<(), Error> {
+        self.assert(layouter, a, b, true)
+    }
+
+    /// Constrain `a <= b`.
+    pub fn assert_le(
+        &self,
+        layouter: impl Layouter<F>,
+        a: &RangeConstrained<F>,
+        b: &RangeConstrained<F>,
+    ) -> Result<(), Error> {
+        self.assert(layouter, a, b, false)
+    }
+
+    /// Constrain `a > b`.
+    pub fn assert_gt(
+        &self,
+        layouter: impl Layouter<F>,
+        a: &RangeConstrained<F>,
+        b: &RangeConstrained<F>,
+    ) -> Result<(), Error> {
+        self.assert(layouter, b, a, true)
+    }
+
+    /// Constrain `a >= b`.
+    pub fn assert_ge(
+        &self,
+        layouter: impl Layouter<F>,
+        a: &RangeConstrained<F>,
+        b: &RangeConstrained<F>,
+    ) -> Result<(), Error> {
+        self.assert(layouter, b, a, false)
+    }
+
+    // `a < b` if strict, `a <= b` otherwise
+    fn compare(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: &RangeConstrained<F>,
+        b: &RangeConstrained<F>,
+        strict: bool,
+    ) -> Result<AssignedCell<Assigned<F>, F>, Error> {
+        let two_pow_n = F::from(1u64 << N);
+        let offset = if strict {
+            two_pow_n - F::one()
+        } else {
+            two_pow_n
+        };
+
+        let (diff, bit) = layouter.assign_region(
+            || "Compare",
+            |mut region| {
+                let diff = self.assign_diff(&mut region, a, b, offset)?;
+
+                self.config.q_bool.enable(&mut region, 0)?;
+                let bit = diff.value().map(|diff| {
+                    let top = diff.evaluate().le_bits()[N];
+                    Assigned::from(if top { F::one() } else { F::zero() })
+                });
+                let bit = region.assign_advice(|| "diff >> N", self.config.bit, 0, || bit)?;
+
+                Ok((diff, bit))
+            },
+        )?;
+
+        // the lowest N bits of diff, leaving the top bit in the final running sum
+        let decomposition = self.config.decompose.assign_lowest_bits(
+            layouter.namespace(|| "Decompose diff"),
+            diff,
+            N,
+        )?;
+
+        layouter.assign_region(
+            || "Top bit",
+            |mut region| region.constrain_equal(decomposition.remainder().cell(), bit.cell()),
+        )?;
+
+        Ok(bit)
+    }
+
+    // `a < b` if strict, `a <= b` otherwise
+    fn assert(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: &RangeConstrained<F>,
+        b: &RangeConstrained<F>,
+        strict: bool,
+    ) -> Result<(), Error> {
+        let offset = if strict { -F::one() } else { F::zero() };
+
+        let diff = layouter.assign_region(
+            || "Assert comparison",
+            |mut region| self.assign_diff(&mut region, a, b, offset),
+        )?;
+
+        // b - a - 1 (resp. b - a) fits in N bits
+        self.config
+            .decompose
+            .assign(layouter.namespace(|| "Decompose diff"), diff, N)?;
+
+        Ok(())
+    }
+
+    // Copy `a` and `b` and witness `diff = b - a + offset` on the first row.
+    // Fails with `Error::Synthesis` if either input is wider than N bits.
+    fn assign_diff(
+        &self,
+        region: &mut Region<'_, F>,
+        a: &RangeConstrained<F>,
+        b: &RangeConstrained<F>,
+        offset: F,
+    ) -> Result<AssignedCell<Assigned<F>, F>, Error> {
+        if a.num_bits() > N || b.num_bits() > N {
+            return Err(Error::Synthesis);
+        }
+        self.config.q_compare.enable(region, 0)?;
+
+        region.assign_fixed(|| "offset", self.config.offset, 0, || Value::known(offset))?;
+        let a = a.cell().copy_advice(|| "a", region, self.config.a, 0)?;
+        let b = b.cell().copy_advice(|| "b", region, self.config.b, 0)?;
+
+        let diff = b.value().copied() - a.value().copied() + Value::known(Assigned::from(offset));
+        region.assign_advice(|| "b - a + offset", self.config.diff, 0, || diff)
+    }
+}
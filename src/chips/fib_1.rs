@@ -1,6 +1,8 @@
-use halo2_proofs::{arithmetic::Field, circuit::*, plonk::*, poly::Rotation};
 use std::marker::PhantomData;
 
+use crate::backend::{circuit::*, plonk::*, poly::Rotation, Field};
+use crate::chips::fibonacci::FibonacciInstructions;
+
 type AdviceColumnType<F> = (AssignedCell<F, F>, AssignedCell<F, F>, AssignedCell<F, F>);
 
 #[derive(Debug, Clone)]
@@ -130,3 +132,83 @@ impl<F: Field> FibonacciChip<F> {
         layouter.constrain_instance(cell.cell(), self.config.instance, row)
     }
 }
+
+impl<F: Field> FibonacciInstructions<F> for FibonacciChip<F> {
+    type Config = FibonacciConfig;
+
+    fn construct(config: Self::Config) -> Self {
+        FibonacciChip::construct(config)
+    }
+
+    fn configure_columns(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let col_a = meta.advice_column();
+        let col_b = meta.advice_column();
+        let col_c = meta.advice_column();
+        let instance = meta.instance_column();
+
+        FibonacciChip::configure(meta, [col_a, col_b, col_c], instance)
+    }
+
+    // one row for the initial terms, then one row per term from F(3) to F(n)
+    fn num_rows(n: usize) -> usize {
+        n - 1
+    }
+
+    fn load_initial(
+        &self,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(AssignedCell<F, F>, AssignedCell<F, F>), Error> {
+        layouter.assign_region(
+            || "initial terms",
+            |mut region| {
+                let a_cell = region.assign_advice_from_instance(
+                    || "f(0)",
+                    self.config.instance,
+                    0,
+                    self.config.advice[0],
+                    0,
+                )?;
+
+                let b_cell = region.assign_advice_from_instance(
+                    || "f(1)",
+                    self.config.instance,
+                    1,
+                    self.config.advice[1],
+                    0,
+                )?;
+
+                Ok((a_cell, b_cell))
+            },
+        )
+    }
+
+    fn extend(
+        &self,
+        mut layouter: impl Layouter<F>,
+        initial: (&AssignedCell<F, F>, &AssignedCell<F, F>),
+        n: usize,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        assert!(n >= 3);
+
+        let (a_cell, b_cell) = initial;
+        let mut prev_b = b_cell.clone();
+        let mut prev_c = self.assign_row(layouter.namespace(|| "next row"), a_cell, b_cell)?;
+
+        for _i in 3..n {
+            let c_cell = self.assign_row(layouter.namespace(|| "next row"), &prev_b, &prev_c)?;
+            prev_b = prev_c;
+            prev_c = c_cell;
+        }
+
+        Ok(prev_c)
+    }
+
+    fn expose_public(
+        &self,
+        layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+        row: usize,
+    ) -> Result<(), Error> {
+        FibonacciChip::expose_public(self, layouter, cell, row)
+    }
+}
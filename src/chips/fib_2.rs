@@ -1,6 +1,8 @@
-use halo2_proofs::{arithmetic::Field, circuit::*, plonk::*, poly::Rotation};
 use std::marker::PhantomData;
 
+use crate::backend::{circuit::*, plonk::*, poly::Rotation, Field};
+use crate::chips::fibonacci::FibonacciInstructions;
+
 #[derive(Debug, Clone)]
 pub struct FibonacciConfig {
     pub advice: [Column<Advice>; 2],
@@ -38,12 +40,15 @@ impl<F: Field> FibonacciChip<F> {
         meta.create_gate("fibonacci", |meta| {
             // col_a | col_b | selector
             //   a       b        s
-            //           c
+            //   a'      c
             let a = meta.query_advice(col_a, Rotation::cur());
             let b = meta.query_advice(col_b, Rotation::cur());
+            let next_a = meta.query_advice(col_a, Rotation::next());
             let c = meta.query_advice(col_b, Rotation::next());
             let s = meta.query_selector(selector);
-            vec![s * (a + b - c)]
+            // `b` is carried over to the next row, otherwise every `a` after
+            // the first row would be free
+            vec![s.clone() * (a + b.clone() - c), s * (next_a - b)]
         });
 
         FibonacciConfig {
@@ -61,9 +66,7 @@ impl<F: Field> FibonacciChip<F> {
         layouter.assign_region(
             || "entire fibonacci table",
             |mut region| {
-                self.config.selector.enable(&mut region, 0)?;
-
-                let mut a_cell = region.assign_advice_from_instance(
+                let a_cell = region.assign_advice_from_instance(
                     || "f(0)",
                     self.config.instance,
                     0, // absolute row index of the instance column i.e. the `a` in `vec![a, b, out]`
@@ -71,7 +74,7 @@ impl<F: Field> FibonacciChip<F> {
                     0,
                 )?;
 
-                let mut b_cell = region.assign_advice_from_instance(
+                let b_cell = region.assign_advice_from_instance(
                     || "f(1)",
                     self.config.instance,
                     1, // absolute row index of the instance column i.e. the `b` in `vec![a, b, out]`
@@ -79,33 +82,46 @@ impl<F: Field> FibonacciChip<F> {
                     0,
                 )?;
 
-                for row in 1..nrows {
-                    if row < nrows - 1 {
-                        self.config.selector.enable(&mut region, row)?;
-                    }
-
-                    let c_cell = region.assign_advice(
-                        || "advice",
-                        self.config.advice[1],
-                        row,
-                        || a_cell.value().copied() + b_cell.value(),
-                    )?;
-
-                    a_cell = region.assign_advice(
-                        || "a",
-                        self.config.advice[0],
-                        row,
-                        || b_cell.value().copied(),
-                    )?;
-
-                    b_cell = c_cell;
-                }
-
-                Ok(b_cell)
+                self.assign_table(&mut region, a_cell, b_cell, nrows)
             },
         )
     }
 
+    // Fill `nrows` rows of the table from the first row `a_cell`, `b_cell`
+    fn assign_table(
+        &self,
+        region: &mut Region<'_, F>,
+        mut a_cell: AssignedCell<F, F>,
+        mut b_cell: AssignedCell<F, F>,
+        nrows: usize,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        self.config.selector.enable(region, 0)?;
+
+        for row in 1..nrows {
+            if row < nrows - 1 {
+                self.config.selector.enable(region, row)?;
+            }
+
+            let c_cell = region.assign_advice(
+                || "advice",
+                self.config.advice[1],
+                row,
+                || a_cell.value().copied() + b_cell.value(),
+            )?;
+
+            a_cell = region.assign_advice(
+                || "a",
+                self.config.advice[0],
+                row,
+                || b_cell.value().copied(),
+            )?;
+
+            b_cell = c_cell;
+        }
+
+        Ok(b_cell)
+    }
+
     // Ensure a cell is equal to the value in the instance column
     pub fn expose_public(
         &self,
@@ -116,3 +132,86 @@ impl<F: Field> FibonacciChip<F> {
         layouter.constrain_instance(cell.cell(), self.config.instance, row)
     }
 }
+
+impl<F: Field> FibonacciInstructions<F> for FibonacciChip<F> {
+    type Config = FibonacciConfig;
+
+    fn construct(config: Self::Config) -> Self {
+        FibonacciChip::construct(config)
+    }
+
+    fn configure_columns(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let col_a = meta.advice_column();
+        let col_b = meta.advice_column();
+        let instance = meta.instance_column();
+
+        FibonacciChip::configure(meta, [col_a, col_b], instance)
+    }
+
+    // one row for the initial terms, then the table from F(2) to F(n)
+    fn num_rows(n: usize) -> usize {
+        n
+    }
+
+    fn load_initial(
+        &self,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(AssignedCell<F, F>, AssignedCell<F, F>), Error> {
+        layouter.assign_region(
+            || "initial terms",
+            |mut region| {
+                let a_cell = region.assign_advice_from_instance(
+                    || "f(0)",
+                    self.config.instance,
+                    0,
+                    self.config.advice[0],
+                    0,
+                )?;
+
+                let b_cell = region.assign_advice_from_instance(
+                    || "f(1)",
+                    self.config.instance,
+                    1,
+                    self.config.advice[1],
+                    0,
+                )?;
+
+                Ok((a_cell, b_cell))
+            },
+        )
+    }
+
+    fn extend(
+        &self,
+        mut layouter: impl Layouter<F>,
+        initial: (&AssignedCell<F, F>, &AssignedCell<F, F>),
+        n: usize,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        assert!(n >= 3);
+
+        layouter.assign_region(
+            || "entire fibonacci table",
+            |mut region| {
+                let a_cell =
+                    initial
+                        .0
+                        .copy_advice(|| "f(0)", &mut region, self.config.advice[0], 0)?;
+                let b_cell =
+                    initial
+                        .1
+                        .copy_advice(|| "f(1)", &mut region, self.config.advice[1], 0)?;
+
+                self.assign_table(&mut region, a_cell, b_cell, n - 1)
+            },
+        )
+    }
+
+    fn expose_public(
+        &self,
+        layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+        row: usize,
+    ) -> Result<(), Error> {
+        FibonacciChip::expose_public(self, layouter, cell, row)
+    }
+}
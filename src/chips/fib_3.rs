@@ -1,5 +1,6 @@
-use halo2_proofs::{arithmetic::Field, circuit::*, plonk::*, poly::Rotation};
-use std::marker::PhantomData;
+use crate::backend::{circuit::*, plonk::*, Field};
+use crate::chips::fibonacci::FibonacciInstructions;
+use crate::chips::linear_recurrence::{LinearRecurrenceChip, LinearRecurrenceConfig};
 
 #[derive(Debug, Clone)]
 pub struct FibonacciConfig {
@@ -8,17 +9,29 @@ pub struct FibonacciConfig {
     pub selector: Selector,
 }
 
+/// The linear recurrence of order 2 with coefficients `[1, 1]`, whose gate
+///
+///     advice | selector
+///       a         s
+///       b
+///       c
+///
+/// is `s * (b + a - c)`.
 pub struct FibonacciChip<F: Field> {
     config: FibonacciConfig,
-    _marker: PhantomData<F>,
+    recurrence: LinearRecurrenceChip<F, 2>,
 }
 
 impl<F: Field> FibonacciChip<F> {
     pub fn construct(config: FibonacciConfig) -> Self {
-        Self {
-            config,
-            _marker: PhantomData,
-        }
+        let recurrence = LinearRecurrenceChip::construct(LinearRecurrenceConfig {
+            advice: config.advice,
+            instance: config.instance,
+            selector: config.selector,
+            coefficients: Self::coefficients(),
+        });
+
+        Self { config, recurrence }
     }
 
     pub fn configure(
@@ -26,87 +39,102 @@ impl<F: Field> FibonacciChip<F> {
         advice: Column<Advice>,
         instance: Column<Instance>,
     ) -> FibonacciConfig {
-        let col_advice = advice;
-        let selector = meta.selector();
-
-        meta.enable_equality(col_advice);
-        meta.enable_equality(instance);
-
-        // create a gate to enforce a constraint
-        meta.create_gate("fibonacci", |meta| {
-            //  advice | selector
-            //    a         s
-            //    b
-            //    c
-            let a = meta.query_advice(col_advice, Rotation::cur());
-            let b = meta.query_advice(col_advice, Rotation::next());
-            let c = meta.query_advice(col_advice, Rotation(2));
-            let s = meta.query_selector(selector);
-            vec![s * (a + b - c)]
-        });
+        let config = LinearRecurrenceChip::configure(meta, advice, instance, Self::coefficients());
 
         FibonacciConfig {
-            advice: col_advice,
-            instance,
-            selector,
+            advice: config.advice,
+            instance: config.instance,
+            selector: config.selector,
         }
     }
 
+    fn coefficients() -> [F; 2] {
+        [F::one(), F::one()]
+    }
+
     pub fn assign(
         &self,
-        mut layouter: impl Layouter<F>,
+        layouter: impl Layouter<F>,
         nrows: usize,
     ) -> Result<AssignedCell<F, F>, Error> {
+        self.recurrence.assign(layouter, nrows)
+    }
+
+    // Ensure a cell is equal to the value in the instance column
+    pub fn expose_public(
+        &self,
+        layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+        row: usize,
+    ) -> Result<(), Error> {
+        self.recurrence.expose_public(layouter, cell, row)
+    }
+}
+
+impl<F: Field> FibonacciInstructions<F> for FibonacciChip<F> {
+    type Config = FibonacciConfig;
+
+    fn construct(config: Self::Config) -> Self {
+        FibonacciChip::construct(config)
+    }
+
+    fn configure_columns(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = meta.advice_column();
+        let instance = meta.instance_column();
+
+        FibonacciChip::configure(meta, advice, instance)
+    }
+
+    // two rows for the initial terms, then the table from F(1) to F(n)
+    fn num_rows(n: usize) -> usize {
+        n + 2
+    }
+
+    fn load_initial(
+        &self,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(AssignedCell<F, F>, AssignedCell<F, F>), Error> {
         layouter.assign_region(
-            || "entire fibonacci table",
+            || "initial terms",
             |mut region| {
-                self.config.selector.enable(&mut region, 0)?;
-                self.config.selector.enable(&mut region, 1)?;
-
-                let mut a_cell = region.assign_advice_from_instance(
+                let a_cell = region.assign_advice_from_instance(
                     || "f(0)",
                     self.config.instance,
-                    0, // absolute row index of the instance column i.e. the `a` in `vec![a, b, out]`
+                    0,
                     self.config.advice,
                     0,
                 )?;
 
-                let mut b_cell = region.assign_advice_from_instance(
+                let b_cell = region.assign_advice_from_instance(
                     || "f(1)",
                     self.config.instance,
-                    1, // absolute row index of the instance column i.e. the `b` in `vec![a, b, out]`
+                    1,
                     self.config.advice,
                     1,
                 )?;
 
-                for row in 2..nrows {
-                    if row < nrows - 2 {
-                        self.config.selector.enable(&mut region, row)?;
-                    }
-
-                    let c_cell = region.assign_advice(
-                        || "advice",
-                        self.config.advice,
-                        row,
-                        || a_cell.value().copied() + b_cell.value().copied(),
-                    )?;
-
-                    a_cell = b_cell;
-                    b_cell = c_cell;
-                }
-
-                Ok(b_cell)
+                Ok((a_cell, b_cell))
             },
         )
     }
 
-    // Ensure a cell is equal to the value in the instance column
-    pub fn expose_public(
+    fn extend(
         &self,
-        mut layouter: impl Layouter<F>,
+        layouter: impl Layouter<F>,
+        initial: (&AssignedCell<F, F>, &AssignedCell<F, F>),
+        n: usize,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        assert!(n >= 3);
+
+        self.recurrence.extend(layouter, [initial.0, initial.1], n)
+    }
+
+    fn expose_public(
+        &self,
+        layouter: impl Layouter<F>,
         cell: &AssignedCell<F, F>,
         row: usize,
     ) -> Result<(), Error> {
-        layouter.constrain_instance(cell.cell(), self.config.instance, row)
+        FibonacciChip::expose_public(self, layouter, cell, row)
     }
 }
@@ -0,0 +1,172 @@
+use std::marker::PhantomData;
+
+use crate::backend::{circuit::*, plonk::*, poly::Rotation, Field};
+
+/// Computes F(n) with the fast doubling identities, one row per bit of n:
+///
+///     F(2m)     = F(m) * (2 * F(m + 1) - F(m))
+///     F(2m + 1) = F(m)^2 + F(m + 1)^2
+///
+/// Starting from (F(0), F(1)) = (0, 1) and m = 0, every row consumes the next
+/// bit of n from the most significant one and moves (F(m), F(m + 1)) to
+/// (F(2m), F(2m + 1)) when the bit is 0, or to (F(2m + 1), F(2m + 2)) when it is
+/// 1. `acc` accumulates the bits so that the last row holds m = n.
+///
+/// | bit | a      | b          | acc | selector |
+/// | --- | ------ | ---------- | --- | -------- |
+/// | b_0 | 0      | 1          | 0   | s        |
+/// | b_1 | F(m_1) | F(m_1 + 1) | m_1 | s        |
+/// | ... | ...    | ...        | ... | ...      |
+/// |     | F(n)   | F(n + 1)   | n   |          |
+#[derive(Debug, Clone)]
+pub struct FibDoublingConfig {
+    pub bit: Column<Advice>,
+    pub a: Column<Advice>,
+    pub b: Column<Advice>,
+    pub acc: Column<Advice>,
+    pub instance: Column<Instance>,
+    pub selector: Selector,
+}
+
+pub struct FibDoublingChip<F: Field> {
+    config: FibDoublingConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field + From<u64>> FibDoublingChip<F> {
+    pub fn construct(config: FibDoublingConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    /// The initial row is assigned from constants.
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 4],
+        instance: Column<Instance>,
+    ) -> FibDoublingConfig {
+        let [bit, a, b, acc] = advice;
+        let selector = meta.selector();
+
+        meta.enable_equality(a);
+        meta.enable_equality(b);
+        meta.enable_equality(acc);
+        meta.enable_equality(instance);
+
+        meta.create_gate("fibonacci doubling", |meta| {
+            let s = meta.query_selector(selector);
+            let bit = meta.query_advice(bit, Rotation::cur());
+            let a_cur = meta.query_advice(a, Rotation::cur());
+            let b_cur = meta.query_advice(b, Rotation::cur());
+            let acc_cur = meta.query_advice(acc, Rotation::cur());
+            let a_next = meta.query_advice(a, Rotation::next());
+            let b_next = meta.query_advice(b, Rotation::next());
+            let acc_next = meta.query_advice(acc, Rotation::next());
+
+            let one = Expression::Constant(F::from(1));
+            let two = Expression::Constant(F::from(2));
+
+            // F(2m) and F(2m + 1)
+            let even = a_cur.clone() * (two.clone() * b_cur.clone() - a_cur.clone());
+            let odd = a_cur.clone() * a_cur + b_cur.clone() * b_cur;
+
+            Constraints::with_selector(
+                s,
+                [
+                    ("bit is boolean", bit.clone() * (one - bit.clone())),
+                    // bit = 0: F(2m), bit = 1: F(2m + 1)
+                    (
+                        "next a",
+                        even.clone() + bit.clone() * (odd.clone() - even.clone()) - a_next,
+                    ),
+                    // bit = 0: F(2m + 1), bit = 1: F(2m + 2) = F(2m) + F(2m + 1)
+                    ("next b", odd + bit.clone() * even - b_next),
+                    ("next acc", two * acc_cur + bit - acc_next),
+                ],
+            )
+        });
+
+        FibDoublingConfig {
+            bit,
+            a,
+            b,
+            acc,
+            instance,
+            selector,
+        }
+    }
+
+    /// Assign one row per bit of `n`, most significant first, and return the
+    /// cells holding `n` and `F(n)`.
+    pub fn assign(
+        &self,
+        mut layouter: impl Layouter<F>,
+        n: Value<u64>,
+        num_bits: usize,
+    ) -> Result<(AssignedCell<F, F>, AssignedCell<F, F>), Error> {
+        assert!(num_bits > 0 && num_bits <= 64);
+
+        layouter.assign_region(
+            || "fibonacci doubling",
+            |mut region| {
+                let mut a_cell =
+                    region.assign_advice_from_constant(|| "F(0)", self.config.a, 0, F::from(0))?;
+                let mut b_cell =
+                    region.assign_advice_from_constant(|| "F(1)", self.config.b, 0, F::from(1))?;
+                let mut acc_cell = region.assign_advice_from_constant(
+                    || "m = 0",
+                    self.config.acc,
+                    0,
+                    F::from(0),
+                )?;
+
+                for row in 0..num_bits {
+                    self.config.selector.enable(&mut region, row)?;
+
+                    let bit = n.map(|n| (n >> (num_bits - 1 - row)) & 1);
+                    region.assign_advice(
+                        || format!("bit {}", num_bits - 1 - row),
+                        self.config.bit,
+                        row,
+                        || bit.map(F::from),
+                    )?;
+
+                    let a = a_cell.value().copied();
+                    let b = b_cell.value().copied();
+                    let even = a * (b + b - a);
+                    let odd = a * a + b * b;
+                    let is_one = bit.map(|bit| bit == 1);
+
+                    let a_next = is_one
+                        .zip(even.zip(odd))
+                        .map(|(is_one, (even, odd))| if is_one { odd } else { even });
+                    let b_next =
+                        is_one
+                            .zip(even.zip(odd))
+                            .map(|(is_one, (even, odd))| if is_one { even + odd } else { odd });
+                    let acc_next =
+                        acc_cell.value().copied() * Value::known(F::from(2)) + bit.map(F::from);
+
+                    a_cell = region.assign_advice(|| "a", self.config.a, row + 1, || a_next)?;
+                    b_cell = region.assign_advice(|| "b", self.config.b, row + 1, || b_next)?;
+                    acc_cell =
+                        region.assign_advice(|| "acc", self.config.acc, row + 1, || acc_next)?;
+                }
+
+                Ok((acc_cell, a_cell))
+            },
+        )
+    }
+
+    // Ensure a cell is equal to the value in the instance column
+    pub fn expose_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+        row: usize,
+    ) -> Result<(), Error> {
+        layouter.constrain_instance(cell.cell(), self.config.instance, row)
+    }
+}
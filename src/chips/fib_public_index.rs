@@ -0,0 +1,214 @@
+use std::marker::PhantomData;
+
+use crate::backend::{circuit::*, plonk::*, poly::Rotation, Field};
+
+/// Config of a Fibonacci table with a fixed capacity whose exposed term is
+/// picked by a public index `n`, so the layout does not depend on `n`.
+///
+/// | a        | b        | n   | inv              | active | out   | index | selector |
+/// | -------- | -------- | --- | ---------------- | ------ | ----- | ----- | -------- |
+/// | F(1)     | F(2)     | n   | 1 / (n - 1)      | 1      | 0     | 1     | s        |
+/// | F(2)     | F(3)     | n   | 1 / (n - 2)      | 1      | ...   | 2     | s        |
+/// | ...      | ...      | ... | ...              | ...    | ...   | ...   | ...      |
+/// | F(N + 1) | F(N + 2) | n   |                  | 0      | F(n)  | N + 1 |          |
+///
+/// `eq = 1 - (n - index) * inv` is 1 exactly on the row where `index = n`. The
+/// `active` flag starts at 1 and drops to 0 after that row, which happens once
+/// and only once when `1 <= n <= N`. `out` accumulates `eq * a`.
+#[derive(Debug, Clone)]
+pub struct FibonacciPublicIndexConfig {
+    pub a: Column<Advice>,
+    pub b: Column<Advice>,
+    pub n: Column<Advice>,
+    pub inv: Column<Advice>,
+    pub active: Column<Advice>,
+    pub out: Column<Advice>,
+    pub index: Column<Fixed>,
+    pub instance: Column<Instance>,
+    pub selector: Selector,
+}
+
+pub struct FibonacciPublicIndexChip<F: Field> {
+    config: FibonacciPublicIndexConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field + From<u64>> FibonacciPublicIndexChip<F> {
+    pub fn construct(config: FibonacciPublicIndexConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    /// `active` and `out` are initialized from constants.
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 6],
+        index: Column<Fixed>,
+        instance: Column<Instance>,
+    ) -> FibonacciPublicIndexConfig {
+        let [a, b, n, inv, active, out] = advice;
+        let selector = meta.selector();
+
+        for column in [a, b, n, active, out] {
+            meta.enable_equality(column);
+        }
+        meta.enable_equality(instance);
+
+        meta.create_gate("fibonacci public index", |meta| {
+            let s = meta.query_selector(selector);
+            let a_cur = meta.query_advice(a, Rotation::cur());
+            let b_cur = meta.query_advice(b, Rotation::cur());
+            let n_cur = meta.query_advice(n, Rotation::cur());
+            let inv = meta.query_advice(inv, Rotation::cur());
+            let active_cur = meta.query_advice(active, Rotation::cur());
+            let out_cur = meta.query_advice(out, Rotation::cur());
+            let index = meta.query_fixed(index, Rotation::cur());
+            let a_next = meta.query_advice(a, Rotation::next());
+            let b_next = meta.query_advice(b, Rotation::next());
+            let n_next = meta.query_advice(n, Rotation::next());
+            let active_next = meta.query_advice(active, Rotation::next());
+            let out_next = meta.query_advice(out, Rotation::next());
+
+            let one = Expression::Constant(F::from(1));
+
+            // eq = 1 iff index = n
+            let diff = n_cur.clone() - index;
+            let eq = one.clone() - diff.clone() * inv;
+
+            Constraints::with_selector(
+                s,
+                [
+                    ("index equals n", diff * eq.clone()),
+                    ("next a", a_next - b_cur.clone()),
+                    ("next b", b_next - (a_cur.clone() + b_cur)),
+                    ("n is constant", n_next - n_cur),
+                    (
+                        "active is boolean",
+                        active_cur.clone() * (one - active_cur.clone()),
+                    ),
+                    (
+                        "active drops after n",
+                        active_next - (active_cur - eq.clone()),
+                    ),
+                    ("out accumulates F(n)", out_next - (out_cur + eq * a_cur)),
+                ],
+            )
+        });
+
+        FibonacciPublicIndexConfig {
+            a,
+            b,
+            n,
+            inv,
+            active,
+            out,
+            index,
+            instance,
+            selector,
+        }
+    }
+
+    /// Assign `capacity` steps, copying `F(1)`, `F(2)` and `n` from the instance
+    /// rows `0`, `1` and `n_row`, and return the cell holding `F(n)`.
+    pub fn assign(
+        &self,
+        mut layouter: impl Layouter<F>,
+        capacity: usize,
+        n_row: usize,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        assert!(capacity > 0);
+
+        layouter.assign_region(
+            || "fibonacci public index",
+            |mut region| {
+                let mut a_cell = region.assign_advice_from_instance(
+                    || "f(1)",
+                    self.config.instance,
+                    0,
+                    self.config.a,
+                    0,
+                )?;
+                let mut b_cell = region.assign_advice_from_instance(
+                    || "f(2)",
+                    self.config.instance,
+                    1,
+                    self.config.b,
+                    0,
+                )?;
+                let mut n_cell = region.assign_advice_from_instance(
+                    || "n",
+                    self.config.instance,
+                    n_row,
+                    self.config.n,
+                    0,
+                )?;
+                let mut active_cell = region.assign_advice_from_constant(
+                    || "active",
+                    self.config.active,
+                    0,
+                    F::from(1),
+                )?;
+                let mut out_cell =
+                    region.assign_advice_from_constant(|| "out", self.config.out, 0, F::from(0))?;
+
+                for row in 0..capacity {
+                    self.config.selector.enable(&mut region, row)?;
+
+                    let index = F::from(row as u64 + 1);
+                    region.assign_fixed(
+                        || "index",
+                        self.config.index,
+                        row,
+                        || Value::known(index),
+                    )?;
+
+                    let diff = n_cell.value().map(|n| *n - index);
+                    let inv = diff.map(|diff| diff.invert().unwrap_or(F::from(0)));
+                    region.assign_advice(|| "inv", self.config.inv, row, || inv)?;
+                    let eq = diff.zip(inv).map(|(diff, inv)| F::from(1) - diff * inv);
+
+                    let a = a_cell.value().copied();
+                    let b = b_cell.value().copied();
+                    let active = active_cell.value().copied() - eq;
+                    let out = out_cell.value().copied() + eq * a;
+                    let n = n_cell.value().copied();
+
+                    a_cell = region.assign_advice(|| "a", self.config.a, row + 1, || b)?;
+                    b_cell = region.assign_advice(|| "b", self.config.b, row + 1, || a + b)?;
+                    n_cell = region.assign_advice(|| "n", self.config.n, row + 1, || n)?;
+                    active_cell = region.assign_advice(
+                        || "active",
+                        self.config.active,
+                        row + 1,
+                        || active,
+                    )?;
+                    out_cell = region.assign_advice(|| "out", self.config.out, row + 1, || out)?;
+                }
+
+                region.assign_fixed(
+                    || "index",
+                    self.config.index,
+                    capacity,
+                    || Value::known(F::from(capacity as u64 + 1)),
+                )?;
+
+                // n was found in the table
+                region.constrain_constant(active_cell.cell(), F::from(0))?;
+
+                Ok(out_cell)
+            },
+        )
+    }
+
+    // Ensure a cell is equal to the value in the instance column
+    pub fn expose_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+        row: usize,
+    ) -> Result<(), Error> {
+        layouter.constrain_instance(cell.cell(), self.config.instance, row)
+    }
+}
@@ -0,0 +1,177 @@
+use std::marker::PhantomData;
+
+use crate::backend::{circuit::*, plonk::*, poly::Rotation, Field};
+
+/// Config of a Fibonacci table packing `W` steps into each row of `W + 1`
+/// advice columns. Consecutive rows overlap by one term:
+///
+/// | c_0      | c_1          | ... | c_W          | q_step | q_chain |
+/// | -------- | ------------ | --- | ------------ | ------ | ------- |
+/// | f(1)     | f(2)         | ... | f(W + 1)     | 1      | 1       |
+/// | f(W + 1) | f(W + 2)     | ... | f(2W + 1)    | 1      | 1       |
+/// | ...      | ...          | ... | ...          | 1      | 0       |
+#[derive(Debug, Clone)]
+pub struct FibWideConfig<const W: usize> {
+    pub advice: Vec<Column<Advice>>,
+    pub instance: Column<Instance>,
+    pub q_step: Selector,
+    pub q_chain: Selector,
+}
+
+pub struct FibWideChip<F: Field, const W: usize> {
+    config: FibWideConfig<W>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field, const W: usize> FibWideChip<F, W> {
+    pub fn construct(config: FibWideConfig<W>) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: &[Column<Advice>],
+        instance: Column<Instance>,
+    ) -> FibWideConfig<W> {
+        assert!(W > 0);
+        assert_eq!(advice.len(), W + 1, "fib_wide needs W + 1 advice columns");
+
+        let q_step = meta.selector();
+        let q_chain = meta.selector();
+
+        for column in advice {
+            meta.enable_equality(*column);
+        }
+        meta.enable_equality(instance);
+
+        // with W = 1 every step crosses a row
+        if W > 1 {
+            meta.create_gate("fibonacci steps in a row", |meta| {
+                let s = meta.query_selector(q_step);
+                let terms: Vec<_> = advice
+                    .iter()
+                    .map(|column| meta.query_advice(*column, Rotation::cur()))
+                    .collect();
+
+                terms
+                    .windows(3)
+                    .map(|t| s.clone() * (t[0].clone() + t[1].clone() - t[2].clone()))
+                    .collect::<Vec<_>>()
+            });
+        }
+
+        meta.create_gate("fibonacci step to next row", |meta| {
+            //  c_0      | ... | c_{W-1} | c_W
+            //  ...      | ... | a       | b
+            //  b        | c   |
+            let s = meta.query_selector(q_chain);
+            let a = meta.query_advice(advice[W - 1], Rotation::cur());
+            let b = meta.query_advice(advice[W], Rotation::cur());
+            let next_b = meta.query_advice(advice[0], Rotation::next());
+            let c = meta.query_advice(advice[1], Rotation::next());
+
+            vec![s.clone() * (next_b - b.clone()), s * (a + b - c)]
+        });
+
+        FibWideConfig {
+            advice: advice.to_vec(),
+            instance,
+            q_step,
+            q_chain,
+        }
+    }
+
+    /// Rows needed to reach `F(n)`: every row adds `W` terms to the first one.
+    pub fn num_rows(n: usize) -> usize {
+        (n - 2) / W + 1
+    }
+
+    pub fn assign(
+        &self,
+        mut layouter: impl Layouter<F>,
+        n: usize,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        assert!(n >= 3);
+
+        let nrows = Self::num_rows(n);
+
+        layouter.assign_region(
+            || "entire fibonacci table",
+            |mut region| {
+                for row in 0..nrows {
+                    self.config.q_step.enable(&mut region, row)?;
+                    if row < nrows - 1 {
+                        self.config.q_chain.enable(&mut region, row)?;
+                    }
+                }
+
+                let mut a_cell = region.assign_advice_from_instance(
+                    || "f(0)",
+                    self.config.instance,
+                    0, // absolute row index of the instance column i.e. the `a` in `vec![a, b, out]`
+                    self.config.advice[0],
+                    0,
+                )?;
+
+                let mut b_cell = region.assign_advice_from_instance(
+                    || "f(1)",
+                    self.config.instance,
+                    1, // absolute row index of the instance column i.e. the `b` in `vec![a, b, out]`
+                    self.config.advice[1],
+                    0,
+                )?;
+
+                // index of the term in b_cell
+                let mut index = 2;
+                let mut out_cell = None;
+
+                for row in 0..nrows {
+                    let first_col = if row == 0 {
+                        2
+                    } else {
+                        // repeat the last term of the previous row
+                        b_cell = region.assign_advice(
+                            || "b",
+                            self.config.advice[0],
+                            row,
+                            || b_cell.value().copied(),
+                        )?;
+                        1
+                    };
+
+                    for col in first_col..=W {
+                        let c_cell = region.assign_advice(
+                            || "advice",
+                            self.config.advice[col],
+                            row,
+                            || a_cell.value().copied() + b_cell.value(),
+                        )?;
+
+                        a_cell = b_cell;
+                        b_cell = c_cell;
+                        index += 1;
+
+                        if index == n {
+                            out_cell = Some(b_cell.clone());
+                        }
+                    }
+                }
+
+                Ok(out_cell.expect("F(n) is in the last row"))
+            },
+        )
+    }
+
+    // Ensure a cell is equal to the value in the instance column
+    pub fn expose_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+        row: usize,
+    ) -> Result<(), Error> {
+        layouter.constrain_instance(cell.cell(), self.config.instance, row)
+    }
+}
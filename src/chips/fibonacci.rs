@@ -0,0 +1,40 @@
+use std::fmt::Debug;
+
+use crate::backend::{circuit::*, plonk::*, Field};
+
+/// Instructions shared by the Fibonacci chips of `fib_1`, `fib_2` and `fib_3`,
+/// so a circuit can be written once against any of their layouts.
+pub trait FibonacciInstructions<F: Field>: Sized {
+    type Config: Clone + Debug;
+
+    fn construct(config: Self::Config) -> Self;
+
+    /// Allocate the columns of the chip and configure its gate.
+    fn configure_columns(meta: &mut ConstraintSystem<F>) -> Self::Config;
+
+    /// Rows used by `load_initial` followed by `extend` up to `F(n)`.
+    fn num_rows(n: usize) -> usize;
+
+    /// Copy `F(1)` and `F(2)` from the instance rows 0 and 1.
+    fn load_initial(
+        &self,
+        layouter: impl Layouter<F>,
+    ) -> Result<(AssignedCell<F, F>, AssignedCell<F, F>), Error>;
+
+    /// Extend the sequence from the cells of `F(1)` and `F(2)` and return the
+    /// cell of `F(n)`.
+    fn extend(
+        &self,
+        layouter: impl Layouter<F>,
+        initial: (&AssignedCell<F, F>, &AssignedCell<F, F>),
+        n: usize,
+    ) -> Result<AssignedCell<F, F>, Error>;
+
+    // Ensure a cell is equal to the value in the instance column
+    fn expose_public(
+        &self,
+        layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+        row: usize,
+    ) -> Result<(), Error>;
+}
@@ -0,0 +1,176 @@
+use std::marker::PhantomData;
+
+use crate::backend::{circuit::*, plonk::*, poly::Rotation, Field};
+
+/// Config of a recurrence of order `ORDER` with constant coefficients
+///
+///     f(n) = c_1 * f(n - 1) + c_2 * f(n - 2) + ... + c_ORDER * f(n - ORDER)
+///
+/// where `coefficients = [c_1, ..., c_ORDER]`. Fibonacci is `[1, 1]`.
+#[derive(Debug, Clone)]
+pub struct LinearRecurrenceConfig<F: Field, const ORDER: usize> {
+    pub advice: Column<Advice>,
+    pub instance: Column<Instance>,
+    pub selector: Selector,
+    pub coefficients: [F; ORDER],
+}
+
+pub struct LinearRecurrenceChip<F: Field, const ORDER: usize> {
+    config: LinearRecurrenceConfig<F, ORDER>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field, const ORDER: usize> LinearRecurrenceChip<F, ORDER> {
+    pub fn construct(config: LinearRecurrenceConfig<F, ORDER>) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: Column<Advice>,
+        instance: Column<Instance>,
+        coefficients: [F; ORDER],
+    ) -> LinearRecurrenceConfig<F, ORDER> {
+        assert!(ORDER > 0);
+
+        let selector = meta.selector();
+
+        meta.enable_equality(advice);
+        meta.enable_equality(instance);
+
+        // create a gate to enforce a constraint
+        meta.create_gate("linear recurrence", |meta| {
+            //  advice          | selector
+            //  f(n - ORDER)    |    s
+            //  ...             |
+            //  f(n - 1)        |
+            //  f(n)            |
+            let s = meta.query_selector(selector);
+            let terms: Vec<_> = (0..=ORDER)
+                .map(|i| meta.query_advice(advice, Rotation(i as i32)))
+                .collect();
+
+            // c_i * f(n - i) where f(n - i) is on rotation ORDER - i
+            let sum = coefficients
+                .iter()
+                .enumerate()
+                .map(|(i, c)| Expression::Constant(*c) * terms[ORDER - 1 - i].clone())
+                .reduce(|acc, term| acc + term)
+                .unwrap();
+
+            vec![s * (sum - terms[ORDER].clone())]
+        });
+
+        LinearRecurrenceConfig {
+            advice,
+            instance,
+            selector,
+            coefficients,
+        }
+    }
+
+    /// Assign `nrows` terms, copying the first `ORDER` of them from the instance
+    /// column, and return the cell of the last term.
+    pub fn assign(
+        &self,
+        mut layouter: impl Layouter<F>,
+        nrows: usize,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        assert!(nrows > ORDER);
+
+        layouter.assign_region(
+            || "entire recurrence table",
+            |mut region| {
+                let window = (0..ORDER)
+                    .map(|row| {
+                        region.assign_advice_from_instance(
+                            || format!("f({})", row),
+                            self.config.instance,
+                            row, // absolute row index of the instance column
+                            self.config.advice,
+                            row,
+                        )
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                self.assign_table(&mut region, window, nrows)
+            },
+        )
+    }
+
+    /// Assign `nrows` terms, copying the first `ORDER` of them from `initial`,
+    /// and return the cell of the last term.
+    pub fn extend(
+        &self,
+        mut layouter: impl Layouter<F>,
+        initial: [&AssignedCell<F, F>; ORDER],
+        nrows: usize,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        assert!(nrows > ORDER);
+
+        layouter.assign_region(
+            || "entire recurrence table",
+            |mut region| {
+                let window = initial
+                    .iter()
+                    .enumerate()
+                    .map(|(row, cell)| {
+                        cell.copy_advice(
+                            || format!("f({})", row),
+                            &mut region,
+                            self.config.advice,
+                            row,
+                        )
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                self.assign_table(&mut region, window, nrows)
+            },
+        )
+    }
+
+    // Fill `nrows` rows of the table from the first `ORDER` terms in `window`,
+    // oldest first
+    fn assign_table(
+        &self,
+        region: &mut Region<'_, F>,
+        mut window: Vec<AssignedCell<F, F>>,
+        nrows: usize,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        // the gate on row r reads rows r to r + ORDER
+        for row in 0..nrows - ORDER {
+            self.config.selector.enable(region, row)?;
+        }
+
+        for row in ORDER..nrows {
+            let value = self
+                .config
+                .coefficients
+                .iter()
+                .zip(window.iter().rev())
+                .map(|(c, cell)| cell.value().map(|v| *v * c))
+                .reduce(|acc, term| acc + term)
+                .unwrap();
+
+            let cell = region.assign_advice(|| "advice", self.config.advice, row, || value)?;
+
+            window.remove(0);
+            window.push(cell);
+        }
+
+        Ok(window.pop().unwrap())
+    }
+
+    // Ensure a cell is equal to the value in the instance column
+    pub fn expose_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+        row: usize,
+    ) -> Result<(), Error> {
+        layouter.constrain_instance(cell.cell(), self.config.instance, row)
+    }
+}
@@ -1,6 +1,12 @@
+pub mod comparison;
 pub mod fib_1;
 pub mod fib_2;
 pub mod fib_3;
+pub mod fib_doubling;
+pub mod fib_public_index;
+pub mod fib_wide;
+pub mod fibonacci;
+pub mod linear_recurrence;
 pub mod range_check_1;
 pub mod range_check_2;
 pub mod range_check_2_table;
@@ -8,3 +14,8 @@ pub mod range_check_3;
 pub mod range_check_3_table;
 pub mod range_check_4;
 pub mod range_check_4_table;
+pub mod range_check_canonical;
+pub mod range_check_interval;
+pub mod range_check_signed;
+pub mod range_constrained;
+pub mod table_registry;
@@ -1,11 +1,12 @@
 use std::marker::PhantomData;
 
-use halo2_proofs_zcash::{
-    arithmetic::FieldExt,
+use crate::backend::{
     circuit::{Layouter, Value},
     plonk::{Advice, Assigned, Column, ConstraintSystem, Constraints, Error, Expression, Selector},
     poly::Rotation,
+    Field,
 };
+use crate::chips::range_constrained::{range_bits, RangeConstrained};
 
 #[derive(Debug, Clone)]
 pub struct RangeCheckConfig {
@@ -13,12 +14,12 @@ pub struct RangeCheckConfig {
     selector: Selector,
 }
 
-pub struct RangeCheckChip<F: FieldExt, const RANGE: usize> {
+pub struct RangeCheckChip<F: Field, const RANGE: usize> {
     pub config: RangeCheckConfig,
     pub _marker: PhantomData<F>,
 }
 
-impl<F: FieldExt, const RANGE: usize> RangeCheckChip<F, RANGE> {
+impl<F: Field, const RANGE: usize> RangeCheckChip<F, RANGE> {
     pub fn construct(config: RangeCheckConfig) -> Self {
         Self {
             config,
@@ -56,7 +57,7 @@ impl<F: FieldExt, const RANGE: usize> RangeCheckChip<F, RANGE> {
         &self,
         mut layouter: impl Layouter<F>,
         value: Value<Assigned<F>>,
-    ) -> Result<(), Error> {
+    ) -> Result<RangeConstrained<F>, Error> {
         layouter.assign_region(
             || "Assign value",
             |mut region| {
@@ -64,9 +65,10 @@ impl<F: FieldExt, const RANGE: usize> RangeCheckChip<F, RANGE> {
 
                 self.config.selector.enable(&mut region, offset)?;
 
-                region.assign_advice(|| "value", self.config.value, offset, || value)
+                region
+                    .assign_advice(|| "value", self.config.value, offset, || value)
+                    .map(|cell| RangeConstrained::new(cell, range_bits(RANGE)))
             },
-        )?;
-        Ok(())
+        )
     }
 }
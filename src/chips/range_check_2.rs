@@ -1,34 +1,32 @@
 use std::marker::PhantomData;
 
-use halo2_proofs_zcash::{
-    arithmetic::FieldExt,
+use crate::backend::{
     circuit::{AssignedCell, Layouter, Value},
+    lookup,
     plonk::{Advice, Assigned, Column, ConstraintSystem, Constraints, Error, Expression, Selector},
     poly::Rotation,
+    Field,
 };
-
 use crate::chips::range_check_2_table::RangeCheckTableConfig;
-
-/// A range-constrained value in the circuit produced by the RangeCheckConfig.
-#[derive(Debug, Clone)]
-pub struct RangeConstrained<F: FieldExt, const RANGE: usize>(AssignedCell<Assigned<F>, F>);
+use crate::chips::range_constrained::{range_bits, RangeConstrained};
+use crate::chips::table_registry::TableRegistry;
 
 #[derive(Debug, Clone)]
-pub struct RangeCheckConfig<F: FieldExt, const RANGE: usize, const LOOKUP_TABLE_RANGE: usize> {
+pub struct RangeCheckConfig<F: Field, const RANGE: usize, const LOOKUP_TABLE_RANGE: usize> {
     // We want to use simple lookup for smaller range, and lookup table for
     // larger range. Thus LOOKUP_TABLE_RANGE is bigger than RANGE
     value: Column<Advice>,
     q_range_check: Selector,
     q_lookup: Selector,
-    pub table: RangeCheckTableConfig<F, LOOKUP_TABLE_RANGE>,
+    table: RangeCheckTableConfig<F, LOOKUP_TABLE_RANGE>,
 }
 
-pub struct RangeCheckChip<F: FieldExt, const RANGE: usize, const LOOKUP_TABLE_RANGE: usize> {
+pub struct RangeCheckChip<F: Field, const RANGE: usize, const LOOKUP_TABLE_RANGE: usize> {
     pub config: RangeCheckConfig<F, RANGE, LOOKUP_TABLE_RANGE>,
     pub _marker: PhantomData<F>,
 }
 
-impl<F: FieldExt, const RANGE: usize, const LOOKUP_TABLE_RANGE: usize>
+impl<F: Field, const RANGE: usize, const LOOKUP_TABLE_RANGE: usize>
     RangeCheckChip<F, RANGE, LOOKUP_TABLE_RANGE>
 {
     pub fn construct(config: RangeCheckConfig<F, RANGE, LOOKUP_TABLE_RANGE>) -> Self {
@@ -41,11 +39,12 @@ impl<F: FieldExt, const RANGE: usize, const LOOKUP_TABLE_RANGE: usize>
     pub fn configure(
         meta: &mut ConstraintSystem<F>,
         advice_col: Column<Advice>,
+        tables: &mut TableRegistry,
     ) -> RangeCheckConfig<F, RANGE, LOOKUP_TABLE_RANGE> {
         let q_range_check = meta.selector();
         let q_lookup = meta.complex_selector();
 
-        let table = RangeCheckTableConfig::configure(meta);
+        let table = RangeCheckTableConfig::configure(meta, tables);
 
         //  value | q_range_check   | q_lookup | table_value |
         //    v   |      1          |   0      |     0       |
@@ -65,7 +64,7 @@ impl<F: FieldExt, const RANGE: usize, const LOOKUP_TABLE_RANGE: usize>
 
         // Lookup
         // Check that a value is contained within a lookup table of values 0..RANGE
-        meta.lookup(|virtual_cells| {
+        lookup(meta, "Range Check", |virtual_cells| {
             let q_lookup = virtual_cells.query_selector(q_lookup);
             let lookup_value = virtual_cells.query_advice(advice_col, Rotation::cur());
 
@@ -84,9 +83,11 @@ impl<F: FieldExt, const RANGE: usize, const LOOKUP_TABLE_RANGE: usize>
         &self,
         mut layouter: impl Layouter<F>,
         value: Value<Assigned<F>>,
-    ) -> Result<RangeConstrained<F, RANGE>, Error> {
+    ) -> Result<RangeConstrained<F>, Error> {
         // do not support if the range is bigger than the lookup table range
 
+        self.config.table.handle.check_loaded()?;
+
         layouter.assign_region(
             || "Assign simple",
             |mut region| {
@@ -96,7 +97,7 @@ impl<F: FieldExt, const RANGE: usize, const LOOKUP_TABLE_RANGE: usize>
 
                 region
                     .assign_advice(|| "value", self.config.value, offset, || value)
-                    .map(RangeConstrained)
+                    .map(|cell| RangeConstrained::new(cell, range_bits(RANGE)))
             },
         )
     }
@@ -105,7 +106,9 @@ impl<F: FieldExt, const RANGE: usize, const LOOKUP_TABLE_RANGE: usize>
         &self,
         mut layouter: impl Layouter<F>,
         value: Value<Assigned<F>>,
-    ) -> Result<RangeConstrained<F, RANGE>, Error> {
+    ) -> Result<RangeConstrained<F>, Error> {
+        self.config.table.handle.check_loaded()?;
+
         layouter.assign_region(
             || "Assign lookup table",
             |mut region| {
@@ -115,7 +118,86 @@ impl<F: FieldExt, const RANGE: usize, const LOOKUP_TABLE_RANGE: usize>
 
                 region
                     .assign_advice(|| "value", self.config.value, offset, || value)
-                    .map(RangeConstrained)
+                    .map(|cell| RangeConstrained::new(cell, range_bits(LOOKUP_TABLE_RANGE)))
+            },
+        )
+    }
+
+    /// Range-check `values` with the expression, one per row of a single region.
+    pub fn assign_simple_batch(
+        &self,
+        mut layouter: impl Layouter<F>,
+        values: &[Value<Assigned<F>>],
+    ) -> Result<Vec<RangeConstrained<F>>, Error> {
+        self.config.table.handle.check_loaded()?;
+
+        layouter.assign_region(
+            || "Assign simple batch",
+            |mut region| {
+                values
+                    .iter()
+                    .enumerate()
+                    .map(|(offset, value)| {
+                        self.config.q_range_check.enable(&mut region, offset)?;
+
+                        region
+                            .assign_advice(|| "value", self.config.value, offset, || *value)
+                            .map(|cell| RangeConstrained::new(cell, range_bits(RANGE)))
+                    })
+                    .collect()
+            },
+        )
+    }
+
+    /// Range-check `values` with the lookup table, one per row of a single region.
+    pub fn assign_lookup_table_batch(
+        &self,
+        mut layouter: impl Layouter<F>,
+        values: &[Value<Assigned<F>>],
+    ) -> Result<Vec<RangeConstrained<F>>, Error> {
+        self.config.table.handle.check_loaded()?;
+
+        layouter.assign_region(
+            || "Assign lookup table batch",
+            |mut region| {
+                values
+                    .iter()
+                    .enumerate()
+                    .map(|(offset, value)| {
+                        self.config.q_lookup.enable(&mut region, offset)?;
+
+                        region
+                            .assign_advice(|| "value", self.config.value, offset, || *value)
+                            .map(|cell| RangeConstrained::new(cell, range_bits(LOOKUP_TABLE_RANGE)))
+                    })
+                    .collect()
+            },
+        )
+    }
+
+    /// Same as `assign_lookup_table_batch`, for values that are already assigned.
+    /// The value column needs equality enabled by the circuit.
+    pub fn copy_lookup_table_batch(
+        &self,
+        mut layouter: impl Layouter<F>,
+        values: &[AssignedCell<Assigned<F>, F>],
+    ) -> Result<Vec<RangeConstrained<F>>, Error> {
+        self.config.table.handle.check_loaded()?;
+
+        layouter.assign_region(
+            || "Copy lookup table batch",
+            |mut region| {
+                values
+                    .iter()
+                    .enumerate()
+                    .map(|(offset, value)| {
+                        self.config.q_lookup.enable(&mut region, offset)?;
+
+                        value
+                            .copy_advice(|| "value", &mut region, self.config.value, offset)
+                            .map(|cell| RangeConstrained::new(cell, range_bits(LOOKUP_TABLE_RANGE)))
+                    })
+                    .collect()
             },
         )
     }
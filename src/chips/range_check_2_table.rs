@@ -1,44 +1,28 @@
 use std::marker::PhantomData;
 
-use halo2_proofs_zcash::{
-    arithmetic::FieldExt,
-    circuit::{Layouter, Value},
-    plonk::{ConstraintSystem, Error, TableColumn},
+use crate::backend::{
+    plonk::{ConstraintSystem, TableColumn},
+    Field,
 };
+use crate::chips::table_registry::{TableHandle, TableRegistry};
 
 // A lookup table of values of NUM_BITS length
 // e.g. NUM_BITS = 8, values = [0..255]
 #[derive(Clone, Debug)]
-pub struct RangeCheckTableConfig<F: FieldExt, const LOOKUP_TABLE_RANGE: usize> {
+pub struct RangeCheckTableConfig<F: Field, const LOOKUP_TABLE_RANGE: usize> {
     pub(super) value: TableColumn,
+    pub(super) handle: TableHandle,
     _marker: PhantomData<F>,
 }
 
-impl<F: FieldExt, const LOOKUP_TABLE_RANGE: usize> RangeCheckTableConfig<F, LOOKUP_TABLE_RANGE> {
-    pub(super) fn configure(meta: &mut ConstraintSystem<F>) -> Self {
-        let value = meta.lookup_table_column();
+impl<F: Field, const LOOKUP_TABLE_RANGE: usize> RangeCheckTableConfig<F, LOOKUP_TABLE_RANGE> {
+    pub(super) fn configure(meta: &mut ConstraintSystem<F>, tables: &mut TableRegistry) -> Self {
+        let (value, handle) = tables.values(meta, LOOKUP_TABLE_RANGE);
 
         Self {
             value,
+            handle,
             _marker: PhantomData,
         }
     }
-
-    pub fn load(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
-        layouter.assign_table(
-            || "load range-check table",
-            |mut table| {
-                for (offset, value) in (0..LOOKUP_TABLE_RANGE).enumerate() {
-                    table.assign_cell(
-                        || "num_bits",
-                        self.value,
-                        offset,
-                        || Value::known(F::from(value as u64)),
-                    )?;
-                }
-
-                Ok(())
-            },
-        )
-    }
 }
@@ -1,37 +1,47 @@
 use std::marker::PhantomData;
 
-use halo2_proofs_zcash::{
-    arithmetic::FieldExt,
-    circuit::{AssignedCell, Layouter, Value},
-    plonk::{Advice, Assigned, Column, ConstraintSystem, Error, Expression, Selector},
+use crate::backend::{
+    circuit::{AssignedCell, Layouter, Region, Value},
+    lookup,
+    plonk::{Advice, Assigned, Column, ConstraintSystem, Error, Expression, Instance, Selector},
     poly::Rotation,
+    Field,
 };
-
 use crate::chips::range_check_3_table::RangeCheckTableConfig;
+use crate::chips::range_constrained::RangeConstrained;
+use crate::chips::table_registry::TableRegistry;
 
-/// A range-constrained value in the circuit produced by the RangeCheckConfig.
-#[derive(Debug, Clone)]
-pub struct RangeConstrained<F: FieldExt> {
-    pub(crate) num_bits: AssignedCell<Assigned<F>, F>,
-    pub(crate) assigned_cell: AssignedCell<Assigned<F>, F>,
+/// A bit width known to the verifier, for `assign_exact` and `assign_at_most`.
+#[derive(Debug, Clone, Copy)]
+pub enum PublicNumBits {
+    /// A constant of the circuit, which needs a fixed column enabled for
+    /// constants.
+    Constant(u8),
+    /// A cell of an instance column, which needs equality enabled.
+    Instance {
+        column: Column<Instance>,
+        row: usize,
+    },
 }
 
 #[derive(Debug, Clone)]
-pub struct RangeCheckConfig<F: FieldExt, const LOOKUP_TABLE_RANGE: usize, const NUM_BITS: usize> {
+pub struct RangeCheckConfig<F: Field, const LOOKUP_TABLE_RANGE: usize, const NUM_BITS: usize> {
     // We want to use simple lookup for smaller range, and lookup table for
     // larger range. Thus LOOKUP_TABLE_RANGE is bigger than RANGE
     q_lookup: Selector,
+    // Bounds num_bits on the current row by the public n on the next one
+    q_bound: Selector,
     num_bits_col: Column<Advice>,
     value_col: Column<Advice>,
-    pub table: RangeCheckTableConfig<F, LOOKUP_TABLE_RANGE, NUM_BITS>,
+    table: RangeCheckTableConfig<F, LOOKUP_TABLE_RANGE, NUM_BITS>,
 }
 
-pub struct RangeCheckChip<F: FieldExt, const LOOKUP_TABLE_RANGE: usize, const NUM_BITS: usize> {
+pub struct RangeCheckChip<F: Field, const LOOKUP_TABLE_RANGE: usize, const NUM_BITS: usize> {
     pub config: RangeCheckConfig<F, LOOKUP_TABLE_RANGE, NUM_BITS>,
     pub _marker: PhantomData<F>,
 }
 
-impl<F: FieldExt, const LOOKUP_TABLE_RANGE: usize, const NUM_BITS: usize>
+impl<F: Field, const LOOKUP_TABLE_RANGE: usize, const NUM_BITS: usize>
     RangeCheckChip<F, LOOKUP_TABLE_RANGE, NUM_BITS>
 {
     pub fn construct(config: RangeCheckConfig<F, LOOKUP_TABLE_RANGE, NUM_BITS>) -> Self {
@@ -45,9 +55,14 @@ impl<F: FieldExt, const LOOKUP_TABLE_RANGE: usize, const NUM_BITS: usize>
         cs: &mut ConstraintSystem<F>,
         num_bits_col: Column<Advice>,
         value_col: Column<Advice>,
+        tables: &mut TableRegistry,
     ) -> RangeCheckConfig<F, LOOKUP_TABLE_RANGE, NUM_BITS> {
         let q_lookup = cs.complex_selector();
-        let table = RangeCheckTableConfig::configure(cs);
+        let q_bound = cs.complex_selector();
+        let table = RangeCheckTableConfig::configure(cs, tables);
+
+        // num_bits is copied from, or compared to, a public bit width
+        cs.enable_equality(num_bits_col);
 
         // | lookup_value | q_lookup | table_num_bits | table_value |
         // | ------------ | -------- | -------------- | ----------- |
@@ -56,7 +71,7 @@ impl<F: FieldExt, const LOOKUP_TABLE_RANGE: usize, const NUM_BITS: usize>
         // | v_2          | 1        | 2              | 2           |
         // | v_3          | 1        | 2              | 3           |
         // | ...          | ...      | 3              | 4           |
-        cs.lookup(|virtual_cells| {
+        lookup(cs, "Range Check", |virtual_cells| {
             let q_lookup_expr = virtual_cells.query_selector(q_lookup);
             let num_bits_expr = virtual_cells.query_advice(num_bits_col, Rotation::cur());
             let lookup_value_expr = virtual_cells.query_advice(value_col, Rotation::cur());
@@ -66,16 +81,34 @@ impl<F: FieldExt, const LOOKUP_TABLE_RANGE: usize, const NUM_BITS: usize>
             let default_num_bits_expr = Expression::Constant(F::one()); // 1-bit
             let default_value_expr = Expression::Constant(F::zero()); // 0 is a 1-bit value
 
-            let num_bits_expr =
-                q_lookup_expr.clone() * num_bits_expr + not_q_lookup_expr.clone() * default_num_bits_expr;
-            let value_expr = q_lookup_expr * lookup_value_expr + not_q_lookup_expr * default_value_expr;
+            let num_bits_expr = q_lookup_expr.clone() * num_bits_expr
+                + not_q_lookup_expr.clone() * default_num_bits_expr;
+            let value_expr =
+                q_lookup_expr * lookup_value_expr + not_q_lookup_expr * default_value_expr;
 
             // a vector of tuples (what we want to lookup, the corresponding table column to compare)
             vec![(num_bits_expr, table.num_bits), (value_expr, table.value)]
         });
 
+        // | num_bits | q_lookup | q_bound |
+        // | -------- | -------- | ------- |
+        // | tag      | 1        | 1       |
+        // | n        | 0        | 0       |
+        //
+        // n - tag is looked up in the values [0, 2^NUM_BITS), which fails if
+        // tag > n as the difference wraps around the modulus.
+        lookup(cs, "Range Check num_bits bound", |virtual_cells| {
+            let q_bound_expr = virtual_cells.query_selector(q_bound);
+            let num_bits_expr = virtual_cells.query_advice(num_bits_col, Rotation::cur());
+            let bound_expr = virtual_cells.query_advice(num_bits_col, Rotation::next());
+
+            // when q_bound = 0, 0 is looked up
+            vec![(q_bound_expr * (bound_expr - num_bits_expr), table.value)]
+        });
+
         RangeCheckConfig {
             q_lookup,
+            q_bound,
             num_bits_col,
             value_col,
             table,
@@ -88,6 +121,8 @@ impl<F: FieldExt, const LOOKUP_TABLE_RANGE: usize, const NUM_BITS: usize>
         value: Value<Assigned<F>>,
         num_bits: Value<u8>,
     ) -> Result<RangeConstrained<F>, Error> {
+        self.config.table.handle.check_loaded()?;
+
         layouter.assign_region(
             || "Assign lookup table",
             |mut region| {
@@ -108,11 +143,183 @@ impl<F: FieldExt, const LOOKUP_TABLE_RANGE: usize, const NUM_BITS: usize>
                     .assign_advice(|| "value", self.config.value_col, offset, || value)
                     .unwrap();
 
-                Ok(RangeConstrained {
-                    num_bits: num_bits_assigned_cell,
-                    assigned_cell: value_assigned_cell,
-                })
+                Ok(RangeConstrained::tagged(
+                    value_assigned_cell,
+                    NUM_BITS,
+                    num_bits_assigned_cell,
+                ))
+            },
+        )
+    }
+
+    /// Range-check `value` to have exactly `n` bits, with the tag copied from
+    /// the public `n`.
+    pub fn assign_exact(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: Value<Assigned<F>>,
+        n: PublicNumBits,
+    ) -> Result<RangeConstrained<F>, Error> {
+        self.config.table.handle.check_loaded()?;
+
+        layouter.assign_region(
+            || "Assign exact num_bits",
+            |mut region| {
+                self.config.q_lookup.enable(&mut region, 0)?;
+
+                let num_bits = self.assign_public_num_bits(&mut region, 0, n)?;
+                let value = region.assign_advice(|| "value", self.config.value_col, 0, || value)?;
+
+                Ok(RangeConstrained::tagged(
+                    value,
+                    Self::max_num_bits(n),
+                    num_bits,
+                ))
+            },
+        )
+    }
+
+    /// Range-check `value` to its witnessed `num_bits`, and `num_bits` to be at
+    /// most the public `n`, i.e. `value < 2^n`.
+    pub fn assign_at_most(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: Value<Assigned<F>>,
+        num_bits: Value<u8>,
+        n: PublicNumBits,
+    ) -> Result<RangeConstrained<F>, Error> {
+        self.config.table.handle.check_loaded()?;
+
+        layouter.assign_region(
+            || "Assign bounded num_bits",
+            |mut region| {
+                self.config.q_bound.enable(&mut region, 0)?;
+
+                let num_bits = self.assign_num_bits(&mut region, 0, num_bits)?;
+                let value = region.assign_advice(|| "value", self.config.value_col, 0, || value)?;
+                self.assign_public_num_bits(&mut region, 1, n)?;
+
+                Ok(RangeConstrained::tagged(
+                    value,
+                    Self::max_num_bits(n),
+                    num_bits,
+                ))
             },
         )
     }
+
+    /// Range-check each value to its number of bits with the lookup table, one
+    /// per row of a single region.
+    pub fn assign_lookup_table_batch(
+        &self,
+        mut layouter: impl Layouter<F>,
+        values: &[(Value<Assigned<F>>, Value<u8>)],
+    ) -> Result<Vec<RangeConstrained<F>>, Error> {
+        self.config.table.handle.check_loaded()?;
+
+        layouter.assign_region(
+            || "Assign lookup table batch",
+            |mut region| {
+                values
+                    .iter()
+                    .enumerate()
+                    .map(|(offset, (value, num_bits))| {
+                        let num_bits = self.assign_num_bits(&mut region, offset, *num_bits)?;
+                        let assigned_cell = region.assign_advice(
+                            || "value",
+                            self.config.value_col,
+                            offset,
+                            || *value,
+                        )?;
+
+                        Ok(RangeConstrained::tagged(assigned_cell, NUM_BITS, num_bits))
+                    })
+                    .collect()
+            },
+        )
+    }
+
+    /// Same as `assign_lookup_table_batch`, for values that are already assigned.
+    /// The value column needs equality enabled by the circuit.
+    pub fn copy_lookup_table_batch(
+        &self,
+        mut layouter: impl Layouter<F>,
+        values: &[(AssignedCell<Assigned<F>, F>, Value<u8>)],
+    ) -> Result<Vec<RangeConstrained<F>>, Error> {
+        self.config.table.handle.check_loaded()?;
+
+        layouter.assign_region(
+            || "Copy lookup table batch",
+            |mut region| {
+                values
+                    .iter()
+                    .enumerate()
+                    .map(|(offset, (value, num_bits))| {
+                        let num_bits = self.assign_num_bits(&mut region, offset, *num_bits)?;
+                        let assigned_cell = value.copy_advice(
+                            || "value",
+                            &mut region,
+                            self.config.value_col,
+                            offset,
+                        )?;
+
+                        Ok(RangeConstrained::tagged(assigned_cell, NUM_BITS, num_bits))
+                    })
+                    .collect()
+            },
+        )
+    }
+
+    // Enable the lookup on `offset` and assign num_bits
+    fn assign_num_bits(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        num_bits: Value<u8>,
+    ) -> Result<AssignedCell<Assigned<F>, F>, Error> {
+        self.config.q_lookup.enable(region, offset)?;
+
+        let num_bits_f = num_bits.map(|v| F::from(v as u64));
+        region.assign_advice(
+            || "num_bits",
+            self.config.num_bits_col,
+            offset,
+            || num_bits_f.into(),
+        )
+    }
+
+    // Assign the public `n` to num_bits on `offset`
+    fn assign_public_num_bits(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        n: PublicNumBits,
+    ) -> Result<AssignedCell<Assigned<F>, F>, Error> {
+        match n {
+            PublicNumBits::Constant(n) => region.assign_advice_from_constant(
+                || format!("num_bits = {}", n),
+                self.config.num_bits_col,
+                offset,
+                Assigned::from(F::from(n as u64)),
+            ),
+            PublicNumBits::Instance { column, row } => region
+                .assign_advice_from_instance(
+                    || "public num_bits",
+                    column,
+                    row,
+                    self.config.num_bits_col,
+                    offset,
+                )
+                .map(AssignedCell::from),
+        }
+    }
+
+    // The bound of a value checked against `n`, which is only known to the
+    // circuit for a constant
+    fn max_num_bits(n: PublicNumBits) -> usize {
+        match n {
+            PublicNumBits::Constant(n) => NUM_BITS.min(n as usize),
+            PublicNumBits::Instance { .. } => NUM_BITS,
+        }
+    }
 }
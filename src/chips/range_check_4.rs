@@ -1,8 +1,9 @@
-use ff::PrimeFieldBits;
-use halo2_proofs_zcash::{arithmetic::FieldExt, circuit::*, plonk::*, poly::Rotation};
 use std::marker::PhantomData;
 
+use crate::backend::{circuit::*, lookup, plonk::*, poly::Rotation, Field};
 use crate::chips::range_check_4_table::RangeTableConfig;
+use crate::chips::range_constrained::RangeConstrained;
+use crate::chips::table_registry::TableRegistry;
 
 /// This gadget range-constrains an element witnessed in the circuit to be N bits.
 ///
@@ -51,37 +52,101 @@ use crate::chips::range_check_4_table::RangeTableConfig;
 ///     - z_C is in the range [0..K)
 ///     - z_shifted is in the range [0..K)  (no underflow)
 ///
+/// Alternatively, `ShortRangeCheck::Tagged` does a single lookup of
+/// `(n + 1, c_{C-1} + 2^n)` against the `(num_bits, value)` tagged table. The
+/// values tagged `n + 1` are exactly [2^n, 2^{n+1}), so the lookup holds iff
+/// c_{C-1} is in [0, 2^n). The tag and the offset come from fixed columns on
+/// the row of c_{C-1}, which saves the two rows of `z_shifted` and `shift`.
+///
+/// Each chunk c_i is also witnessed in the `chunk` column, next to z_i, so that
+/// other gadgets can copy the limbs of the decomposition.
+///
+
+/// The cells of a value decomposed by `RangeCheckDecomposeConfig`.
+#[derive(Debug, Clone)]
+pub struct Decomposition<F: Field> {
+    /// The running sum z_0, z_1, ..., z_C, where z_0 is a copy of the value.
+    pub running_sum: Vec<AssignedCell<Assigned<F>, F>>,
+    /// The little-endian chunks c_0, ..., c_{C-1}, constrained to
+    /// c_i = z_i - z_{i+1} * 2^K. The last one is shorter than K bits when
+    /// num_bits is not a multiple of K.
+    pub chunks: Vec<AssignedCell<Assigned<F>, F>>,
+    // The bound of z_0, when z_C is constrained to 0
+    num_bits: Option<usize>,
+}
+
+impl<F: Field> Decomposition<F> {
+    /// The final running sum z_C, i.e. the value shifted right by C * K bits.
+    pub fn remainder(&self) -> &AssignedCell<Assigned<F>, F> {
+        self.running_sum.last().unwrap()
+    }
+
+    /// The decomposed value z_0 with its proven bit width, or `None` for
+    /// `assign_lowest_bits`, which leaves z_C unconstrained.
+    pub fn range_constrained(&self) -> Option<RangeConstrained<F>> {
+        self.num_bits
+            .map(|num_bits| RangeConstrained::new(self.running_sum[0].clone(), num_bits))
+    }
+}
+
+/// How the final partial chunk is range-constrained when N is not a multiple of K.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShortRangeCheck {
+    /// Look up `c_{C-1} * 2^{K - n}` in the untagged table, on two extra rows.
+    Shifted,
+    /// Look up `(n + 1, c_{C-1} + 2^n)` in the tagged table, on no extra rows.
+    Tagged,
+}
 
 #[derive(Debug, Clone)]
 pub struct RangeCheckDecomposeConfig<
-    F: FieldExt + PrimeFieldBits,
+    F: Field,
     const LOOKUP_NUM_BITS: usize,
     const LOOKUP_RANGE: usize,
 > {
     // You'll need an advice column to witness your running sum;
     pub running_sum: Column<Advice>,
+    // An advice column to witness the chunk of each window;
+    pub chunk: Column<Advice>,
     // A selector to constrain the running sum;
     q_decompose: Selector,
     // A selector to handle the final partial chunk
     q_partial_check: Selector,
+    // A selector, and the tag and offset, for the tagged final partial chunk
+    q_short: Selector,
+    short_tag: Column<Fixed>,
+    short_offset: Column<Fixed>,
     // And of course, the K-bit lookup table
-    pub table: RangeTableConfig<F, LOOKUP_NUM_BITS, LOOKUP_RANGE>,
+    table: RangeTableConfig<F, LOOKUP_NUM_BITS, LOOKUP_RANGE>,
     _marker: PhantomData<F>,
 }
 
-impl<F: FieldExt + PrimeFieldBits, const LOOKUP_NUM_BITS: usize, const LOOKUP_RANGE: usize>
+impl<F: Field, const LOOKUP_NUM_BITS: usize, const LOOKUP_RANGE: usize>
     RangeCheckDecomposeConfig<F, LOOKUP_NUM_BITS, LOOKUP_RANGE>
 {
-    pub fn configure(meta: &mut ConstraintSystem<F>, running_sum: Column<Advice>) -> Self {
+    /// Each decomposition is assigned in its own region, so `running_sum` and
+    /// `chunk` can be shared with the columns of other chips. The final running
+    /// sum is constrained to a constant, so the circuit has to enable a fixed
+    /// column with `enable_constant`.
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        running_sum: Column<Advice>,
+        chunk: Column<Advice>,
+        tables: &mut TableRegistry,
+    ) -> Self {
         // Create the needed columns and internal configs.
         let q_decompose = meta.complex_selector();
         let q_partial_check = meta.complex_selector();
-        let table = RangeTableConfig::configure(meta);
+        let q_short = meta.complex_selector();
+        let short_tag = meta.fixed_column();
+        let short_offset = meta.fixed_column();
+        let table = RangeTableConfig::configure(meta, tables);
 
         meta.enable_equality(running_sum);
+        meta.enable_equality(chunk);
 
         // Range-constrain each K-bit chunk `c_i = z_i - z_{i+1} * 2^K` derived from the running sum.
-        meta.lookup(|meta| {
+        lookup(meta, "Range Check chunk", |meta| {
             let q_decompose = meta.query_selector(q_decompose);
 
             // z_i
@@ -89,7 +154,7 @@ impl<F: FieldExt + PrimeFieldBits, const LOOKUP_NUM_BITS: usize, const LOOKUP_RA
             // z_{i+1}
             let z_next = meta.query_advice(running_sum, Rotation::next());
             // c_i = z_i - z_{i+1} * 2^K
-            let chunk = z_cur - z_next * F::from(1u64 << LOOKUP_NUM_BITS);
+            let chunk = z_cur - z_next * two_pow::<F>(LOOKUP_NUM_BITS);
 
             // Lookup default value 0 when q_decompose = 0
             let not_q_decompose = Expression::Constant(F::one()) - q_decompose.clone();
@@ -101,6 +166,19 @@ impl<F: FieldExt + PrimeFieldBits, const LOOKUP_NUM_BITS: usize, const LOOKUP_RA
             )]
         });
 
+        // Witness each chunk c_i = z_i - z_{i+1} * 2^K next to z_i.
+        meta.create_gate("chunk", |meta| {
+            let q_decompose = meta.query_selector(q_decompose);
+            let z_cur = meta.query_advice(running_sum, Rotation::cur());
+            let z_next = meta.query_advice(running_sum, Rotation::next());
+            let chunk = meta.query_advice(chunk, Rotation::cur());
+
+            Constraints::with_selector(
+                q_decompose,
+                [z_cur - z_next * two_pow::<F>(LOOKUP_NUM_BITS) - chunk],
+            )
+        });
+
         // Handle the final partial chunk.
         meta.create_gate("final partial chunk", |meta| {
             let q_partial_check = meta.query_selector(q_partial_check);
@@ -113,7 +191,7 @@ impl<F: FieldExt + PrimeFieldBits, const LOOKUP_NUM_BITS: usize, const LOOKUP_RA
                 // z_C
                 let z_cur = meta.query_advice(running_sum, Rotation::cur());
                 // c_{C-1} = z_{C-1} - z_C * 2^K
-                final_chunk = z_prev - z_cur * F::from(1u64 << LOOKUP_NUM_BITS);
+                final_chunk = z_prev - z_cur * two_pow::<F>(LOOKUP_NUM_BITS);
             }
 
             // shifted_chunk = final_chunk * 2^{K - num_bits} where num_bits < K
@@ -123,7 +201,7 @@ impl<F: FieldExt + PrimeFieldBits, const LOOKUP_NUM_BITS: usize, const LOOKUP_RA
             {
                 // 2^{-num_bits} = 1 / 2^num_bits
                 let inv_two_pow_s = meta.query_advice(running_sum, Rotation(2));
-                let two_pow_k = F::from(1 << LOOKUP_NUM_BITS);
+                let two_pow_k = two_pow::<F>(LOOKUP_NUM_BITS);
                 // z_C = final_chunk * 2^K / 2^num_bits - final_chunk * 2^{K - num_bits}
                 // z_C = c_{C-1} * 2^{K - num_bits} - c_{C-1} * 2^{K - num_bits} = 0
                 let q_partial_check_expr = final_chunk * two_pow_k * inv_two_pow_s - shifted_chunk;
@@ -132,7 +210,7 @@ impl<F: FieldExt + PrimeFieldBits, const LOOKUP_NUM_BITS: usize, const LOOKUP_RA
         });
 
         // Lookup argument for shifted_chunk to ensure it is within K bits (i.e. in the range [0, 2^K))
-        meta.lookup(|meta| {
+        lookup(meta, "Range Check shifted partial chunk", |meta| {
             let q_partial_check = meta.query_selector(q_partial_check);
             let shifted = meta.query_advice(running_sum, Rotation::next());
 
@@ -146,93 +224,279 @@ impl<F: FieldExt + PrimeFieldBits, const LOOKUP_NUM_BITS: usize, const LOOKUP_RA
             )]
         });
 
+        // Tagged lookup of the final partial chunk: (n + 1, c_{C-1} + 2^n)
+        lookup(meta, "Range Check tagged partial chunk", |meta| {
+            let q_short = meta.query_selector(q_short);
+            let chunk = meta.query_advice(chunk, Rotation::cur());
+            let tag = meta.query_fixed(short_tag, Rotation::cur());
+            let offset = meta.query_fixed(short_offset, Rotation::cur());
+
+            // Lookup default (num_bits = 1, value = 0) when q_short = 0
+            let not_q_short = Expression::Constant(F::one()) - q_short.clone();
+            let default_num_bits = Expression::Constant(F::one());
+            let default_value = Expression::Constant(F::zero());
+
+            vec![
+                (
+                    q_short.clone() * tag + not_q_short.clone() * default_num_bits,
+                    table.num_bits,
+                ),
+                (
+                    q_short * (chunk + offset) + not_q_short * default_value,
+                    table.value,
+                ),
+            ]
+        });
+
         Self {
             running_sum,
+            chunk,
             q_decompose,
             q_partial_check,
+            q_short,
+            short_tag,
+            short_offset,
             table,
             _marker: PhantomData,
         }
     }
 
+    /// Decompose `value` into `num_bits` bits and constrain the final running
+    /// sum z_C to be 0, i.e. `value < 2^num_bits`.
     pub fn assign(
         &self,
-        mut layouter: impl Layouter<F>,
+        layouter: impl Layouter<F>,
         value: AssignedCell<Assigned<F>, F>,
         num_bits: usize,
-    ) -> Result<(), Error> {
-        let partial_len = num_bits % LOOKUP_NUM_BITS;
+    ) -> Result<Decomposition<F>, Error> {
+        self.decompose(layouter, value, num_bits, true, ShortRangeCheck::Shifted)
+    }
+
+    /// Same as `assign`, with the given check of the final partial chunk.
+    pub fn assign_with(
+        &self,
+        layouter: impl Layouter<F>,
+        value: AssignedCell<Assigned<F>, F>,
+        num_bits: usize,
+        short: ShortRangeCheck,
+    ) -> Result<Decomposition<F>, Error> {
+        self.decompose(layouter, value, num_bits, true, short)
+    }
+
+    /// Rows used by the region of a decomposition into `num_bits` bits.
+    pub fn num_rows(num_bits: usize, short: ShortRangeCheck) -> usize {
+        let windows = (num_bits + LOOKUP_NUM_BITS - 1) / LOOKUP_NUM_BITS;
+        let partial_rows = match short {
+            ShortRangeCheck::Shifted if num_bits % LOOKUP_NUM_BITS > 0 => 2,
+            _ => 0,
+        };
+
+        // z_0, ..., z_C and the rows of the shifted chunk
+        windows + 1 + partial_rows
+    }
+
+    /// Decompose the lowest `num_bits` bits of `value` without constraining the
+    /// final running sum, which is left in `Decomposition::remainder`.
+    ///
+    /// `num_bits` must be a multiple of K, as a partial last window would only
+    /// be shifted by 2^K.
+    pub fn assign_lowest_bits(
+        &self,
+        layouter: impl Layouter<F>,
+        value: AssignedCell<Assigned<F>, F>,
+        num_bits: usize,
+    ) -> Result<Decomposition<F>, Error> {
+        assert_eq!(
+            num_bits % LOOKUP_NUM_BITS,
+            0,
+            "num_bits must be a multiple of {}",
+            LOOKUP_NUM_BITS
+        );
+        self.decompose(layouter, value, num_bits, false, ShortRangeCheck::Shifted)
+    }
+
+    /// Witness each of `values` and decompose it into `num_bits` bits, with the
+    /// decompositions laid out one after the other in a single region.
+    pub fn assign_batch(
+        &self,
+        mut layouter: impl Layouter<F>,
+        values: &[Value<Assigned<F>>],
+        num_bits: usize,
+        short: ShortRangeCheck,
+    ) -> Result<Vec<Decomposition<F>>, Error> {
+        layouter.assign_region(
+            || "Decompose values",
+            |mut region| {
+                let rows = Self::num_rows(num_bits, short);
+                values
+                    .iter()
+                    .enumerate()
+                    .map(|(i, value)| {
+                        let z = region.assign_advice(
+                            || format!("value {}", i),
+                            self.running_sum,
+                            i * rows,
+                            || *value,
+                        )?;
+                        self.decompose_at(&mut region, i * rows, z, num_bits, true, short)
+                    })
+                    .collect()
+            },
+        )
+    }
 
+    /// Same as `assign_batch`, for values that are already assigned.
+    pub fn copy_batch(
+        &self,
+        mut layouter: impl Layouter<F>,
+        values: &[AssignedCell<Assigned<F>, F>],
+        num_bits: usize,
+        short: ShortRangeCheck,
+    ) -> Result<Vec<Decomposition<F>>, Error> {
         layouter.assign_region(
-            || "Decompose value",
+            || "Decompose values",
             |mut region| {
-                let mut offset = 0;
+                let rows = Self::num_rows(num_bits, short);
+                values
+                    .iter()
+                    .enumerate()
+                    .map(|(i, value)| {
+                        let z = value.copy_advice(
+                            || format!("Copy in value {}", i),
+                            &mut region,
+                            self.running_sum,
+                            i * rows,
+                        )?;
+                        self.decompose_at(&mut region, i * rows, z, num_bits, true, short)
+                    })
+                    .collect()
+            },
+        )
+    }
 
-                // 0. Copy in the witnessed `value` at offset = 0
-                let mut z = value.copy_advice(
+    fn decompose(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: AssignedCell<Assigned<F>, F>,
+        num_bits: usize,
+        strict: bool,
+        short: ShortRangeCheck,
+    ) -> Result<Decomposition<F>, Error> {
+        layouter.assign_region(
+            || "Decompose value",
+            |mut region| {
+                // Copy in the witnessed `value` at offset = 0
+                let z = value.copy_advice(
                     || "Copy in value for decomposition",
                     &mut region,
                     self.running_sum,
-                    offset,
+                    0,
                 )?;
 
-                // Increase offset after copying `value`
-                offset += 1;
-
-                // 1. Compute the interstitial running sum values {z_1, ..., z_C}}
-                let expected_vec_len = if partial_len > 0 {
-                    1 + num_bits / LOOKUP_NUM_BITS
-                } else {
-                    num_bits / LOOKUP_NUM_BITS
-                };
-
-                let running_sum: Vec<_> = value
-                    .value()
-                    .map(|&v| compute_running_sum::<_, LOOKUP_NUM_BITS>(v, num_bits))
-                    .transpose_vec(expected_vec_len);
-
-                // 2. Assign the running sum values
-                for z_i in running_sum.into_iter() {
-                    z = region.assign_advice(
-                        || format!("assign z_{:?}", offset),
-                        self.running_sum,
-                        offset,
-                        || z_i,
-                    )?;
-                    offset += 1;
-                }
-
-                // 3. Make sure to enable the relevant selector on each row of the running sum
-                //    (but not on the row where z_C is witnessed)
-                for offset in 0..(num_bits / LOOKUP_NUM_BITS) {
-                    self.q_decompose.enable(&mut region, offset)?;
-                }
-
-                // 4. Constrain the final running sum `z_C` to be 0.
-                region.constrain_constant(z.cell(), F::zero())?;
-
-                // Handle partial chunk
-                if partial_len > 0 {
-                    // The final chunk
-                    let final_chunk = value.value().map(|v| {
-                        let v: Vec<_> = v
-                            .evaluate()
-                            .to_le_bits()
-                            .iter()
-                            .by_vals()
-                            .take(num_bits)
-                            .collect();
-                        let final_chunk = &v[(num_bits - partial_len)..num_bits];
-                        Assigned::from(F::from(lebs2ip(final_chunk)))
-                    });
-                    self.short_range_check(&mut region, offset - 1, final_chunk, partial_len)?;
-                }
-
-                Ok(())
+                self.decompose_at(&mut region, 0, z, num_bits, strict, short)
             },
         )
     }
 
+    // Decompose the value z_0 witnessed in the running sum at `start`, on the
+    // following `num_rows(num_bits, short)` rows of the region.
+    fn decompose_at(
+        &self,
+        region: &mut Region<'_, F>,
+        start: usize,
+        z: AssignedCell<Assigned<F>, F>,
+        num_bits: usize,
+        strict: bool,
+        short: ShortRangeCheck,
+    ) -> Result<Decomposition<F>, Error> {
+        self.table.handle.check_loaded()?;
+
+        let partial_len = num_bits % LOOKUP_NUM_BITS;
+        let value = z.value().copied();
+        let mut zs = vec![z];
+
+        // Increase offset after `value`
+        let mut offset = start + 1;
+
+        // 1. Compute the interstitial running sum values {z_1, ..., z_C}}
+        let expected_vec_len = if partial_len > 0 {
+            1 + num_bits / LOOKUP_NUM_BITS
+        } else {
+            num_bits / LOOKUP_NUM_BITS
+        };
+
+        let running_sum: Vec<_> = value
+            .map(|v| compute_running_sum::<_, LOOKUP_NUM_BITS>(v, num_bits))
+            .transpose_vec(expected_vec_len);
+
+        // 2. Assign the running sum values
+        for z_i in running_sum.into_iter() {
+            let z = region.assign_advice(
+                || format!("assign z_{:?}", offset - start),
+                self.running_sum,
+                offset,
+                || z_i,
+            )?;
+            zs.push(z);
+            offset += 1;
+        }
+
+        // 3. Make sure to enable the relevant selector on each row of the running sum
+        //    (but not on the row where z_C is witnessed), and witness the chunks.
+        //    The final partial chunk is looked up too, so that together with the
+        //    shifted lookup it is bounded to partial_len bits.
+        let two_pow_k = Value::known(Assigned::from(two_pow::<F>(LOOKUP_NUM_BITS)));
+        let mut chunks = vec![];
+        for (i, z) in zs.windows(2).enumerate() {
+            self.q_decompose.enable(region, start + i)?;
+
+            let chunk = z[0].value().copied() - z[1].value().copied() * two_pow_k;
+            chunks.push(region.assign_advice(
+                || format!("assign c_{:?}", i),
+                self.chunk,
+                start + i,
+                || chunk,
+            )?);
+        }
+
+        // 4. Constrain the final running sum `z_C` to be 0.
+        if strict {
+            region.constrain_constant(zs[expected_vec_len].cell(), F::zero())?;
+        }
+
+        // Handle partial chunk
+        if partial_len > 0 && short == ShortRangeCheck::Tagged {
+            let offset = start + expected_vec_len - 1;
+            self.q_short.enable(region, offset)?;
+            region.assign_fixed(
+                || format!("tag {}", partial_len + 1),
+                self.short_tag,
+                offset,
+                || Value::known(F::from(partial_len as u64 + 1)),
+            )?;
+            region.assign_fixed(
+                || format!("2^{}", partial_len),
+                self.short_offset,
+                offset,
+                || Value::known(two_pow::<F>(partial_len)),
+            )?;
+        } else if partial_len > 0 {
+            // The final chunk
+            let final_chunk = value.map(|v| {
+                let v: Vec<_> = v.evaluate().le_bits().into_iter().take(num_bits).collect();
+                let final_chunk = &v[(num_bits - partial_len)..num_bits];
+                Assigned::from(lebs2f::<F>(final_chunk))
+            });
+            self.short_range_check(region, offset - 1, final_chunk, partial_len)?;
+        }
+
+        Ok(Decomposition {
+            running_sum: zs,
+            chunks,
+            num_bits: strict.then_some(num_bits),
+        })
+    }
+
     /// Constrain `x` to be a partial_len word.
     ///
     /// q_partial_check is enabled on the offset of the final running sum z_C.
@@ -252,7 +516,7 @@ impl<F: FieldExt + PrimeFieldBits, const LOOKUP_NUM_BITS: usize, const LOOKUP_RA
         self.q_partial_check.enable(region, offset)?;
 
         // Assign shifted `element * 2^{K - partial_len}`
-        let shifted = element.into_field() * F::from(1 << (LOOKUP_NUM_BITS - partial_len));
+        let shifted = element.into_field() * two_pow::<F>(LOOKUP_NUM_BITS - partial_len);
         region.assign_advice(
             || format!("element * 2^({}-{})", LOOKUP_NUM_BITS, partial_len),
             self.running_sum,
@@ -261,7 +525,7 @@ impl<F: FieldExt + PrimeFieldBits, const LOOKUP_NUM_BITS: usize, const LOOKUP_RA
         )?;
 
         // Assign 2^{-partial_len} from a fixed column.
-        let inv_two_pow_s = F::from(1 << partial_len).invert().unwrap();
+        let inv_two_pow_s = two_pow::<F>(partial_len).invert().unwrap();
         region.assign_advice_from_constant(
             || format!("2^(-{})", partial_len),
             self.running_sum,
@@ -273,16 +537,20 @@ impl<F: FieldExt + PrimeFieldBits, const LOOKUP_NUM_BITS: usize, const LOOKUP_RA
     }
 }
 
-// little-endian bits to u64
-fn lebs2ip(bits: &[bool]) -> u64 {
-    assert!(bits.len() <= 64);
-    bits.iter()
-        .enumerate()
-        .fold(0u64, |acc, (i, b)| acc + if *b { 1 << i } else { 0 })
+// 2^n as a field element, for any n below the bit width of the field
+fn two_pow<F: Field>(n: usize) -> F {
+    F::from(2u64).pow_vartime([n as u64])
+}
+
+// little-endian bits to a field element
+fn lebs2f<F: Field>(bits: &[bool]) -> F {
+    bits.iter().rev().fold(F::zero(), |acc, b| {
+        acc.double() + if *b { F::one() } else { F::zero() }
+    })
 }
 
 // Function to compute the interstitial running sum values {z_1, ..., z_C}}
-fn compute_running_sum<F: FieldExt + PrimeFieldBits, const LOOKUP_NUM_BITS: usize>(
+fn compute_running_sum<F: Field, const LOOKUP_NUM_BITS: usize>(
     value: Assigned<F>,
     num_bits: usize,
 ) -> Vec<Assigned<F>> {
@@ -292,15 +560,14 @@ fn compute_running_sum<F: FieldExt + PrimeFieldBits, const LOOKUP_NUM_BITS: usiz
     // Get the little-endian bit representation of `value`.
     let value: Vec<_> = value
         .evaluate()
-        .to_le_bits()
-        .iter()
-        .by_vals()
+        .le_bits()
+        .into_iter()
         .take(num_bits)
         .collect();
     for chunk in value.chunks(LOOKUP_NUM_BITS) {
-        let chunk = Assigned::from(F::from(lebs2ip(chunk)));
+        let chunk = Assigned::from(lebs2f::<F>(chunk));
         // z_{i+1} = (z_i - c_i) * 2^{-K}:
-        z = (z - chunk) * Assigned::from(F::from(1u64 << LOOKUP_NUM_BITS)).invert();
+        z = (z - chunk) * Assigned::from(two_pow::<F>(LOOKUP_NUM_BITS)).invert();
         running_sum.push(z);
     }
 
@@ -1,79 +1,33 @@
 use std::marker::PhantomData;
 
-use halo2_proofs_zcash::{
-    arithmetic::FieldExt,
-    circuit::{Layouter, Value},
-    plonk::{ConstraintSystem, Error, TableColumn},
+use crate::backend::{
+    plonk::{ConstraintSystem, TableColumn},
+    Field,
 };
+use crate::chips::table_registry::{TableHandle, TableRegistry};
 
 /// A lookup table of values up to RANGE
 /// e.g. RANGE = 256, values = [0..255]
 /// This table is tagged by an index `k`, where `k` is the number of bits of the element in the `value` column.
 #[derive(Debug, Clone)]
-pub(crate) struct RangeTableConfig<F: FieldExt, const NUM_BITS: usize, const RANGE: usize> {
+pub(crate) struct RangeTableConfig<F: Field, const NUM_BITS: usize, const RANGE: usize> {
     pub(crate) num_bits: TableColumn,
     pub(crate) value: TableColumn,
+    pub(crate) handle: TableHandle,
     _marker: PhantomData<F>,
 }
 
-impl<F: FieldExt, const NUM_BITS: usize, const RANGE: usize> RangeTableConfig<F, NUM_BITS, RANGE> {
-    pub(crate) fn configure(meta: &mut ConstraintSystem<F>) -> Self {
+impl<F: Field, const NUM_BITS: usize, const RANGE: usize> RangeTableConfig<F, NUM_BITS, RANGE> {
+    pub(crate) fn configure(meta: &mut ConstraintSystem<F>, tables: &mut TableRegistry) -> Self {
         assert_eq!(1 << NUM_BITS, RANGE);
 
-        let num_bits = meta.lookup_table_column();
-        let value = meta.lookup_table_column();
+        let (num_bits, value, handle) = tables.tagged(meta, NUM_BITS);
 
         Self {
             num_bits,
             value,
+            handle,
             _marker: PhantomData,
         }
     }
-
-    pub(crate) fn load(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
-        layouter.assign_table(
-            || "load range-check table",
-            |mut table| {
-                let mut offset = 0;
-
-                // Assign (num_bits = 1, value = 0)
-                {
-                    table.assign_cell(
-                        || "assign num_bits",
-                        self.num_bits,
-                        offset,
-                        || Value::known(F::one()),
-                    )?;
-                    table.assign_cell(
-                        || "assign value",
-                        self.value,
-                        offset,
-                        || Value::known(F::zero()),
-                    )?;
-
-                    offset += 1;
-                }
-
-                for num_bits in 1..=NUM_BITS {
-                    for value in (1 << (num_bits - 1))..(1 << num_bits) {
-                        table.assign_cell(
-                            || "assign num_bits",
-                            self.num_bits,
-                            offset,
-                            || Value::known(F::from(num_bits as u64)),
-                        )?;
-                        table.assign_cell(
-                            || "assign value",
-                            self.value,
-                            offset,
-                            || Value::known(F::from(value as u64)),
-                        )?;
-                        offset += 1;
-                    }
-                }
-
-                Ok(())
-            },
-        )
-    }
 }
@@ -0,0 +1,191 @@
+use std::marker::PhantomData;
+
+use crate::backend::{
+    circuit::{AssignedCell, Layouter, Value},
+    plonk::{Advice, Assigned, Column, ConstraintSystem, Constraints, Error, Expression, Selector},
+    poly::Rotation,
+    Field,
+};
+use crate::chips::range_check_4::{Decomposition, RangeCheckDecomposeConfig, ShortRangeCheck};
+use crate::chips::table_registry::TableRegistry;
+
+/// The canonical little-endian bits of a field element, in K-bit chunks.
+#[derive(Debug, Clone)]
+pub struct CanonicalDecomposition<F: Field> {
+    /// The decomposed value.
+    pub value: AssignedCell<Assigned<F>, F>,
+    /// The decomposition of the lowest h = NUM_BITS - 1 bits.
+    pub low: Decomposition<F>,
+    /// The top bit h.
+    pub top: AssignedCell<Assigned<F>, F>,
+}
+
+/// Decomposes a field element into its canonical bits, i.e. the bits of the
+/// integer in [0, p).
+///
+/// A decomposition of `value` into NUM_BITS bits by `RangeCheckDecomposeConfig`
+/// is only unique modulo p: for a small `value`, the bits of `value + p` fit
+/// too. Write the modulus as p = 2^h + t with h = NUM_BITS - 1 and t < 2^h.
+/// Then `value = low + 2^h * top` with `low` in [0, 2^h) and a boolean `top` is
+/// canonical iff `top = 0` or `low < t`. The second case is checked by
+/// decomposing
+///
+///     diff = top * (low + 2^M - t)
+///
+/// into M bits, where M is the bit length of t. When `top = 0` then
+/// `diff = 0`, otherwise `diff < 2^M` iff `low < t`. As 2^M <= 2t, `diff` is
+/// below 2^h + t = p and cannot wrap around the modulus.
+///
+///     | value | low | top | diff                       | q_canonical |
+///     ----------------------------------------------------------------
+///     |   v   | low | top | top * (low + 2^M - t)      |      1      |
+#[derive(Debug, Clone)]
+pub struct CanonicalDecomposeConfig<
+    F: Field,
+    const LOOKUP_NUM_BITS: usize,
+    const LOOKUP_RANGE: usize,
+> {
+    value: Column<Advice>,
+    low: Column<Advice>,
+    top: Column<Advice>,
+    diff: Column<Advice>,
+    q_canonical: Selector,
+    pub decompose: RangeCheckDecomposeConfig<F, LOOKUP_NUM_BITS, LOOKUP_RANGE>,
+}
+
+pub struct CanonicalDecomposeChip<F: Field, const LOOKUP_NUM_BITS: usize, const LOOKUP_RANGE: usize>
+{
+    pub config: CanonicalDecomposeConfig<F, LOOKUP_NUM_BITS, LOOKUP_RANGE>,
+    pub _marker: PhantomData<F>,
+}
+
+impl<F: Field, const LOOKUP_NUM_BITS: usize, const LOOKUP_RANGE: usize>
+    CanonicalDecomposeChip<F, LOOKUP_NUM_BITS, LOOKUP_RANGE>
+{
+    pub fn construct(config: CanonicalDecomposeConfig<F, LOOKUP_NUM_BITS, LOOKUP_RANGE>) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 4],
+        tables: &mut TableRegistry,
+    ) -> CanonicalDecomposeConfig<F, LOOKUP_NUM_BITS, LOOKUP_RANGE> {
+        let [value, low, top, diff] = advice;
+        let q_canonical = meta.selector();
+
+        for column in advice {
+            meta.enable_equality(column);
+        }
+
+        let (_, two_pow_h, _, two_pow_m_minus_t) = Self::modulus();
+
+        meta.create_gate("Canonical decomposition", |meta| {
+            let q_canonical = meta.query_selector(q_canonical);
+            let value = meta.query_advice(value, Rotation::cur());
+            let low = meta.query_advice(low, Rotation::cur());
+            let top = meta.query_advice(top, Rotation::cur());
+            let diff = meta.query_advice(diff, Rotation::cur());
+
+            let one = Expression::Constant(F::one());
+
+            Constraints::with_selector(
+                q_canonical,
+                [
+                    (
+                        "value = low + 2^h * top",
+                        value - low.clone() - top.clone() * two_pow_h,
+                    ),
+                    ("top is boolean", top.clone() * (one - top.clone())),
+                    (
+                        "diff = top * (low + 2^M - t)",
+                        diff - top * (low + Expression::Constant(two_pow_m_minus_t)),
+                    ),
+                ],
+            )
+        });
+
+        let decompose = RangeCheckDecomposeConfig::configure(meta, value, low, tables);
+
+        CanonicalDecomposeConfig {
+            value,
+            low,
+            top,
+            diff,
+            q_canonical,
+            decompose,
+        }
+    }
+
+    /// Returns (h, 2^h, M, 2^M - t) for the modulus p = 2^h + t.
+    pub fn modulus() -> (usize, F, usize, F) {
+        let h = F::NUM_BITS as usize - 1;
+        let two_pow_h = F::from(2u64).pow_vartime([h as u64]);
+
+        // t = p - 2^h, i.e. -2^h in the field
+        let t = -two_pow_h;
+        let m = t.le_bits().iter().rposition(|&b| b).map_or(0, |i| i + 1);
+        assert!(m <= h, "t does not fit in {} bits", h);
+
+        let two_pow_m = F::from(2u64).pow_vartime([m as u64]);
+        (h, two_pow_h, m, two_pow_m - t)
+    }
+
+    /// Decompose `value` into its canonical bits.
+    pub fn assign(
+        &self,
+        layouter: impl Layouter<F>,
+        value: Value<Assigned<F>>,
+    ) -> Result<CanonicalDecomposition<F>, Error> {
+        let (h, ..) = Self::modulus();
+        let top = value.map(|v| v.evaluate().le_bits()[h]);
+
+        self.assign_with_top(layouter, value, top)
+    }
+
+    // Decompose `value` with the given top bit, which is only canonical for the
+    // actual bit h of `value`.
+    pub(crate) fn assign_with_top(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: Value<Assigned<F>>,
+        top: Value<bool>,
+    ) -> Result<CanonicalDecomposition<F>, Error> {
+        let (h, two_pow_h, m, two_pow_m_minus_t) = Self::modulus();
+
+        let (value, low, top, diff) = layouter.assign_region(
+            || "Canonical decomposition",
+            |mut region| {
+                self.config.q_canonical.enable(&mut region, 0)?;
+
+                let top = top.map(|top| Assigned::from(if top { F::one() } else { F::zero() }));
+                let low = value - top * Value::known(Assigned::from(two_pow_h));
+                let diff = top * (low + Value::known(Assigned::from(two_pow_m_minus_t)));
+
+                let value = region.assign_advice(|| "value", self.config.value, 0, || value)?;
+                let low = region.assign_advice(|| "low", self.config.low, 0, || low)?;
+                let top = region.assign_advice(|| "top", self.config.top, 0, || top)?;
+                let diff = region.assign_advice(|| "diff", self.config.diff, 0, || diff)?;
+
+                Ok((value, low, top, diff))
+            },
+        )?;
+
+        let low = self.config.decompose.assign_with(
+            layouter.namespace(|| "Decompose low bits"),
+            low,
+            h,
+            ShortRangeCheck::Tagged,
+        )?;
+
+        // low < t whenever top = 1
+        self.config
+            .decompose
+            .assign(layouter.namespace(|| "Decompose diff"), diff, m)?;
+
+        Ok(CanonicalDecomposition { value, low, top })
+    }
+}
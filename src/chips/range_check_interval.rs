@@ -0,0 +1,200 @@
+use std::marker::PhantomData;
+
+use crate::backend::{
+    circuit::{AssignedCell, Layouter, Value},
+    plonk::{
+        Advice, Assigned, Column, ConstraintSystem, Constraints, Error, Expression, Fixed, Selector,
+    },
+    poly::Rotation,
+    Field,
+};
+use crate::chips::range_check_4::RangeCheckDecomposeConfig;
+use crate::chips::table_registry::TableRegistry;
+
+/// Constrains a value to an interval `[lo, hi)` with arbitrary bounds.
+///
+/// Both offsets `v - lo` and `hi - 1 - v` are witnessed and range-checked to
+/// `[0, R)` for some `R >= hi - lo`. Then `v - lo < R` keeps v below `lo + R`
+/// and `hi - 1 - v < R` keeps it above `hi - 1 - R`, which together leave
+/// exactly `[lo, hi)`.
+///
+/// - If `hi - lo <= SMALL_RANGE`, the offsets are checked with the expression
+///   `(d) * (1 - d) * ... * (SMALL_RANGE - 1 - d)` as in range_check_1.
+/// - Otherwise they are decomposed by `RangeCheckDecomposeConfig` into
+///   `N` bits, the smallest multiple of `LOOKUP_NUM_BITS` with `2^N >= hi - lo`.
+///
+///     | value | diff         | bound  | q_lower | q_upper | q_small |
+///     ------------------------------------------------------------------
+///     |   v   | v - lo       | lo     |    1    |    0    |   0/1   |
+///     |   v   | hi - 1 - v   | hi - 1 |    0    |    1    |   0/1   |
+#[derive(Debug, Clone)]
+pub struct RangeCheckIntervalConfig<
+    F: Field,
+    const SMALL_RANGE: usize,
+    const LOOKUP_NUM_BITS: usize,
+    const LOOKUP_RANGE: usize,
+> {
+    value: Column<Advice>,
+    diff: Column<Advice>,
+    bound: Column<Fixed>,
+    q_lower: Selector,
+    q_upper: Selector,
+    q_small: Selector,
+    pub decompose: RangeCheckDecomposeConfig<F, LOOKUP_NUM_BITS, LOOKUP_RANGE>,
+}
+
+pub struct RangeCheckIntervalChip<
+    F: Field,
+    const SMALL_RANGE: usize,
+    const LOOKUP_NUM_BITS: usize,
+    const LOOKUP_RANGE: usize,
+> {
+    pub config: RangeCheckIntervalConfig<F, SMALL_RANGE, LOOKUP_NUM_BITS, LOOKUP_RANGE>,
+    pub _marker: PhantomData<F>,
+}
+
+impl<
+        F: Field,
+        const SMALL_RANGE: usize,
+        const LOOKUP_NUM_BITS: usize,
+        const LOOKUP_RANGE: usize,
+    > RangeCheckIntervalChip<F, SMALL_RANGE, LOOKUP_NUM_BITS, LOOKUP_RANGE>
+{
+    pub fn construct(
+        config: RangeCheckIntervalConfig<F, SMALL_RANGE, LOOKUP_NUM_BITS, LOOKUP_RANGE>,
+    ) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        value: Column<Advice>,
+        diff: Column<Advice>,
+        bound: Column<Fixed>,
+        running_sum: Column<Advice>,
+        tables: &mut TableRegistry,
+    ) -> RangeCheckIntervalConfig<F, SMALL_RANGE, LOOKUP_NUM_BITS, LOOKUP_RANGE> {
+        assert!(SMALL_RANGE > 0);
+
+        let q_lower = meta.selector();
+        let q_upper = meta.selector();
+        let q_small = meta.selector();
+
+        meta.enable_equality(value);
+        meta.enable_equality(diff);
+
+        meta.create_gate("Interval offsets", |virtual_cells| {
+            let q_lower = virtual_cells.query_selector(q_lower);
+            let q_upper = virtual_cells.query_selector(q_upper);
+            let value = virtual_cells.query_advice(value, Rotation::cur());
+            let diff = virtual_cells.query_advice(diff, Rotation::cur());
+            let bound = virtual_cells.query_fixed(bound, Rotation::cur());
+
+            vec![
+                // diff = v - lo
+                q_lower * (value.clone() - bound.clone() - diff.clone()),
+                // diff = hi - 1 - v
+                q_upper * (bound - value - diff),
+            ]
+        });
+
+        meta.create_gate("Interval small span", |virtual_cells| {
+            let q = virtual_cells.query_selector(q_small);
+            let diff = virtual_cells.query_advice(diff, Rotation::cur());
+
+            // (d) * (1 - d) * (2 - d) * ... * (SMALL_RANGE - 1 - d)
+            let range_check = (1..SMALL_RANGE).fold(diff.clone(), |expr, i| {
+                expr * (Expression::Constant(F::from(i as u64)) - diff.clone())
+            });
+
+            Constraints::with_selector(q, [("range check", range_check)])
+        });
+
+        // the chunks share the `diff` column
+        let decompose = RangeCheckDecomposeConfig::configure(meta, running_sum, diff, tables);
+
+        RangeCheckIntervalConfig {
+            value,
+            diff,
+            bound,
+            q_lower,
+            q_upper,
+            q_small,
+            decompose,
+        }
+    }
+
+    /// Bits of the decomposition of the offsets for a span `hi - lo`.
+    pub fn num_bits(lo: u64, hi: u64) -> usize {
+        assert!(lo < hi);
+
+        let bits = (64 - (hi - lo - 1).leading_zeros()) as usize;
+        let chunks = ((bits + LOOKUP_NUM_BITS - 1) / LOOKUP_NUM_BITS).max(1);
+        chunks * LOOKUP_NUM_BITS
+    }
+
+    /// Constrain `value` to `[lo, hi)` and return its cell.
+    pub fn assign(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: Value<Assigned<F>>,
+        lo: u64,
+        hi: u64,
+    ) -> Result<AssignedCell<Assigned<F>, F>, Error> {
+        assert!(lo < hi, "empty interval [{}, {})", lo, hi);
+
+        let small = hi - lo <= SMALL_RANGE as u64;
+
+        let (value_cell, lower, upper) = layouter.assign_region(
+            || "Assign interval offsets",
+            |mut region| {
+                self.config.q_lower.enable(&mut region, 0)?;
+                self.config.q_upper.enable(&mut region, 1)?;
+                if small {
+                    self.config.q_small.enable(&mut region, 0)?;
+                    self.config.q_small.enable(&mut region, 1)?;
+                }
+
+                let lo_f = F::from(lo);
+                let upper_f = F::from(hi - 1);
+
+                region.assign_fixed(|| "lo", self.config.bound, 0, || Value::known(lo_f))?;
+                region.assign_fixed(|| "hi - 1", self.config.bound, 1, || Value::known(upper_f))?;
+
+                let value_cell =
+                    region.assign_advice(|| "value", self.config.value, 0, || value)?;
+                value_cell.copy_advice(|| "value", &mut region, self.config.value, 1)?;
+
+                let lower = region.assign_advice(
+                    || "v - lo",
+                    self.config.diff,
+                    0,
+                    || value - Value::known(Assigned::from(lo_f)),
+                )?;
+                let upper = region.assign_advice(
+                    || "hi - 1 - v",
+                    self.config.diff,
+                    1,
+                    || Value::known(Assigned::from(upper_f)) - value,
+                )?;
+
+                Ok((value_cell, lower, upper))
+            },
+        )?;
+
+        if !small {
+            let num_bits = Self::num_bits(lo, hi);
+            self.config
+                .decompose
+                .assign(layouter.namespace(|| "v - lo"), lower, num_bits)?;
+            self.config
+                .decompose
+                .assign(layouter.namespace(|| "hi - 1 - v"), upper, num_bits)?;
+        }
+
+        Ok(value_cell)
+    }
+}
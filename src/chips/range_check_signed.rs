@@ -0,0 +1,162 @@
+use std::marker::PhantomData;
+
+use crate::backend::{
+    circuit::{AssignedCell, Layouter, Value},
+    plonk::{Advice, Assigned, Column, ConstraintSystem, Constraints, Error, Expression, Selector},
+    poly::Rotation,
+    Field,
+};
+use crate::chips::range_check_4::RangeCheckDecomposeConfig;
+use crate::chips::table_registry::TableRegistry;
+
+/// A signed value constrained to [-2^{N-1}, 2^{N-1}), with its sign and
+/// absolute value.
+#[derive(Debug, Clone)]
+pub struct SignedRangeConstrained<F: Field> {
+    /// The value, where a negative x is the field element p - |x|.
+    pub value: AssignedCell<Assigned<F>, F>,
+    /// 1 if the value is negative, 0 otherwise.
+    pub sign: AssignedCell<Assigned<F>, F>,
+    /// The absolute value |x|, in [0, 2^{N-1}].
+    pub abs: AssignedCell<Assigned<F>, F>,
+}
+
+/// Constrains a signed value x to [-2^{N-1}, 2^{N-1}).
+///
+/// The sign s is boolean and `abs = x * (1 - 2s)`. Then `abs - s` is
+/// decomposed into N - 1 bits by `RangeCheckDecomposeConfig`:
+///
+/// - if s = 0, then x = abs is in [0, 2^{N-1}).
+/// - if s = 1, then -x - 1 = abs - 1 is in [0, 2^{N-1}), i.e. x is in
+///   [-2^{N-1}, -1]. As 0 is not in there, the sign of 0 is 0.
+///
+///     | value | sign | abs          | diff      | q_signed |
+///     ------------------------------------------------------
+///     |   x   |  s   | x * (1 - 2s) | abs - s   |    1     |
+#[derive(Debug, Clone)]
+pub struct SignedRangeCheckConfig<F: Field, const LOOKUP_NUM_BITS: usize, const LOOKUP_RANGE: usize>
+{
+    value: Column<Advice>,
+    sign: Column<Advice>,
+    abs: Column<Advice>,
+    diff: Column<Advice>,
+    q_signed: Selector,
+    pub decompose: RangeCheckDecomposeConfig<F, LOOKUP_NUM_BITS, LOOKUP_RANGE>,
+}
+
+pub struct SignedRangeCheckChip<F: Field, const LOOKUP_NUM_BITS: usize, const LOOKUP_RANGE: usize> {
+    pub config: SignedRangeCheckConfig<F, LOOKUP_NUM_BITS, LOOKUP_RANGE>,
+    pub _marker: PhantomData<F>,
+}
+
+impl<F: Field, const LOOKUP_NUM_BITS: usize, const LOOKUP_RANGE: usize>
+    SignedRangeCheckChip<F, LOOKUP_NUM_BITS, LOOKUP_RANGE>
+{
+    pub fn construct(config: SignedRangeCheckConfig<F, LOOKUP_NUM_BITS, LOOKUP_RANGE>) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 4],
+        tables: &mut TableRegistry,
+    ) -> SignedRangeCheckConfig<F, LOOKUP_NUM_BITS, LOOKUP_RANGE> {
+        let [value, sign, abs, diff] = advice;
+        let q_signed = meta.selector();
+
+        for column in advice {
+            meta.enable_equality(column);
+        }
+
+        meta.create_gate("Signed value", |meta| {
+            let q_signed = meta.query_selector(q_signed);
+            let value = meta.query_advice(value, Rotation::cur());
+            let sign = meta.query_advice(sign, Rotation::cur());
+            let abs = meta.query_advice(abs, Rotation::cur());
+            let diff = meta.query_advice(diff, Rotation::cur());
+
+            let one = Expression::Constant(F::one());
+            let two = Expression::Constant(F::from(2u64));
+
+            Constraints::with_selector(
+                q_signed,
+                [
+                    (
+                        "sign is boolean",
+                        sign.clone() * (one.clone() - sign.clone()),
+                    ),
+                    (
+                        "abs = x * (1 - 2s)",
+                        abs.clone() - value * (one - two * sign.clone()),
+                    ),
+                    ("diff = abs - s", diff - (abs - sign)),
+                ],
+            )
+        });
+
+        let decompose = RangeCheckDecomposeConfig::configure(meta, value, sign, tables);
+
+        SignedRangeCheckConfig {
+            value,
+            sign,
+            abs,
+            diff,
+            q_signed,
+            decompose,
+        }
+    }
+
+    /// Constrain `value` to [-2^{num_bits-1}, 2^{num_bits-1}).
+    pub fn assign(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: Value<Assigned<F>>,
+        num_bits: usize,
+    ) -> Result<SignedRangeConstrained<F>, Error> {
+        assert!(num_bits > 1, "a signed value needs at least 2 bits");
+
+        let (value, sign, abs, diff) = layouter.assign_region(
+            || "Signed value",
+            |mut region| {
+                self.config.q_signed.enable(&mut region, 0)?;
+
+                // negative values wrap around, so they have high bits set
+                let sign = value.map(|v| {
+                    let negative = v
+                        .evaluate()
+                        .le_bits()
+                        .into_iter()
+                        .skip(num_bits - 1)
+                        .any(|b| b);
+                    Assigned::from(if negative { F::one() } else { F::zero() })
+                });
+                let one = Value::known(Assigned::from(F::one()));
+                let two = Value::known(Assigned::from(F::from(2u64)));
+                let abs = value * (one - two * sign);
+
+                let value = region.assign_advice(|| "x", self.config.value, 0, || value)?;
+                let sign = region.assign_advice(|| "sign", self.config.sign, 0, || sign)?;
+                let abs = region.assign_advice(|| "abs", self.config.abs, 0, || abs)?;
+                let diff = region.assign_advice(
+                    || "abs - sign",
+                    self.config.diff,
+                    0,
+                    || abs.value().copied() - sign.value().copied(),
+                )?;
+
+                Ok((value, sign, abs, diff))
+            },
+        )?;
+
+        self.config.decompose.assign(
+            layouter.namespace(|| "Decompose abs - sign"),
+            diff,
+            num_bits - 1,
+        )?;
+
+        Ok(SignedRangeConstrained { value, sign, abs })
+    }
+}
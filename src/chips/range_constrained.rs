@@ -0,0 +1,184 @@
+use crate::backend::{
+    circuit::{AssignedCell, Layouter, Value},
+    plonk::{Advice, Assigned, Column, ConstraintSystem, Constraints, Error, Selector},
+    poly::Rotation,
+    Field,
+};
+
+/// A value proven to lie in [0, 2^num_bits) by one of the range check chips,
+/// or by adding and multiplying such values.
+#[derive(Debug, Clone)]
+pub struct RangeConstrained<F: Field> {
+    cell: AssignedCell<Assigned<F>, F>,
+    num_bits: usize,
+    num_bits_cell: Option<AssignedCell<Assigned<F>, F>>,
+}
+
+impl<F: Field> RangeConstrained<F> {
+    pub(crate) fn new(cell: AssignedCell<Assigned<F>, F>, num_bits: usize) -> Self {
+        Self {
+            cell,
+            num_bits,
+            num_bits_cell: None,
+        }
+    }
+
+    /// A value looked up in a tagged table, proven to fit in the witnessed
+    /// `num_bits_cell` bits, which is itself at most `num_bits`.
+    pub(crate) fn tagged(
+        cell: AssignedCell<Assigned<F>, F>,
+        num_bits: usize,
+        num_bits_cell: AssignedCell<Assigned<F>, F>,
+    ) -> Self {
+        Self {
+            cell,
+            num_bits,
+            num_bits_cell: Some(num_bits_cell),
+        }
+    }
+
+    /// The cell holding the value.
+    pub fn cell(&self) -> &AssignedCell<Assigned<F>, F> {
+        &self.cell
+    }
+
+    /// The proven bit width of the value.
+    pub fn num_bits(&self) -> usize {
+        self.num_bits
+    }
+
+    /// The witnessed bit width of a value checked by a tagged lookup.
+    pub fn num_bits_cell(&self) -> Option<&AssignedCell<Assigned<F>, F>> {
+        self.num_bits_cell.as_ref()
+    }
+
+    /// The witnessed value.
+    pub fn value(&self) -> Value<F> {
+        self.cell.value().map(|v| v.evaluate())
+    }
+
+    /// The witnessed value as a `u64`, or `None` if the bound is wider than 64
+    /// bits.
+    pub fn value_u64(&self) -> Option<Value<u64>> {
+        (self.num_bits <= 64).then(|| {
+            self.value().map(|v| {
+                v.le_bits()[..64]
+                    .iter()
+                    .rev()
+                    .fold(0, |acc, bit| (acc << 1) | *bit as u64)
+            })
+        })
+    }
+
+    /// Constrain the sum of `self` and `other`, of at most one bit more than
+    /// the wider of the two.
+    pub fn add(
+        &self,
+        layouter: impl Layouter<F>,
+        config: &ArithmeticConfig,
+        other: &Self,
+    ) -> Result<Self, Error> {
+        config.assign(layouter, Op::Add, self, other)
+    }
+
+    /// Constrain the product of `self` and `other`, whose bit width is the sum
+    /// of theirs.
+    pub fn mul(
+        &self,
+        layouter: impl Layouter<F>,
+        config: &ArithmeticConfig,
+        other: &Self,
+    ) -> Result<Self, Error> {
+        config.assign(layouter, Op::Mul, self, other)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Op {
+    Add,
+    Mul,
+}
+
+/// The gates behind `RangeConstrained::add` and `RangeConstrained::mul`.
+///
+///     |  a  |  b  |  c  | q_add | q_mul |
+///     -----------------------------------
+///     |  a  |  b  | a+b |   1   |   0   |
+///     |  a  |  b  | a*b |   0   |   1   |
+#[derive(Debug, Clone)]
+pub struct ArithmeticConfig {
+    advice: [Column<Advice>; 3],
+    q_add: Selector,
+    q_mul: Selector,
+}
+
+impl ArithmeticConfig {
+    pub fn configure<F: Field>(
+        meta: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 3],
+    ) -> Self {
+        for column in advice {
+            meta.enable_equality(column);
+        }
+        let q_add = meta.selector();
+        let q_mul = meta.selector();
+
+        meta.create_gate("add", |meta| {
+            let q = meta.query_selector(q_add);
+            let [a, b, c] = advice.map(|column| meta.query_advice(column, Rotation::cur()));
+            Constraints::with_selector(q, [("c = a + b", a + b - c)])
+        });
+
+        meta.create_gate("mul", |meta| {
+            let q = meta.query_selector(q_mul);
+            let [a, b, c] = advice.map(|column| meta.query_advice(column, Rotation::cur()));
+            Constraints::with_selector(q, [("c = a * b", a * b - c)])
+        });
+
+        Self {
+            advice,
+            q_add,
+            q_mul,
+        }
+    }
+
+    // Copy in `a` and `b` and witness `a op b`, which must stay below the
+    // modulus for the bound to hold. Fails with `Error::Synthesis` otherwise.
+    fn assign<F: Field>(
+        &self,
+        mut layouter: impl Layouter<F>,
+        op: Op,
+        a: &RangeConstrained<F>,
+        b: &RangeConstrained<F>,
+    ) -> Result<RangeConstrained<F>, Error> {
+        let (name, selector, num_bits) = match op {
+            Op::Add => ("add", self.q_add, a.num_bits.max(b.num_bits) + 1),
+            Op::Mul => ("mul", self.q_mul, a.num_bits + b.num_bits),
+        };
+        if num_bits >= F::NUM_BITS as usize {
+            return Err(Error::Synthesis);
+        }
+
+        layouter.assign_region(
+            || name,
+            |mut region| {
+                selector.enable(&mut region, 0)?;
+
+                let a = a.cell.copy_advice(|| "a", &mut region, self.advice[0], 0)?;
+                let b = b.cell.copy_advice(|| "b", &mut region, self.advice[1], 0)?;
+                let c = a.value().zip(b.value()).map(|(a, b)| match op {
+                    Op::Add => *a + *b,
+                    Op::Mul => *a * *b,
+                });
+                region
+                    .assign_advice(|| "c", self.advice[2], 0, || c)
+                    .map(|c| RangeConstrained::new(c, num_bits))
+            },
+        )
+    }
+}
+
+/// The bit width of the values in [0, range).
+pub(crate) fn range_bits(range: usize) -> usize {
+    (usize::BITS - range.saturating_sub(1).leading_zeros()) as usize
+}
@@ -0,0 +1,197 @@
+use std::{cell::Cell, rc::Rc};
+
+use crate::backend::{
+    circuit::{Layouter, Value},
+    plonk::{ConstraintSystem, Error, TableColumn},
+    Field,
+};
+
+/// The contents of a range table. Chips asking for the same contents share a
+/// single table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RangeTable {
+    /// The values [0, range) in one column.
+    Values { range: usize },
+    /// The values [0, 2^num_bits), each tagged with its bit length in a second
+    /// column. 0 is tagged 1, as is 1.
+    Tagged { num_bits: usize },
+}
+
+/// Whether the table of a chip has been loaded by its registry.
+#[derive(Debug, Clone)]
+pub struct TableHandle(Rc<Cell<bool>>);
+
+impl TableHandle {
+    /// Fails if the circuit did not call `TableRegistry::load` before
+    /// assigning the chip.
+    pub fn check_loaded(&self) -> Result<(), Error> {
+        if self.0.get() {
+            Ok(())
+        } else {
+            Err(Error::Synthesis)
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Entry {
+    table: RangeTable,
+    // `[value]` or `[tag, value]`
+    columns: Vec<TableColumn>,
+    loaded: TableHandle,
+}
+
+/// The lookup tables of a circuit, passed to the `configure` of each chip that
+/// needs one. The circuit keeps the registry in its config, calls `load` once at
+/// the start of `synthesize` and `finish` at the end.
+#[derive(Debug, Clone, Default)]
+pub struct TableRegistry {
+    entries: Vec<Entry>,
+}
+
+impl TableRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The tables declared so far, each once.
+    pub fn tables(&self) -> Vec<RangeTable> {
+        self.entries.iter().map(|entry| entry.table).collect()
+    }
+
+    /// The value column of `[0, range)`.
+    pub(crate) fn values<F: Field>(
+        &mut self,
+        meta: &mut ConstraintSystem<F>,
+        range: usize,
+    ) -> (TableColumn, TableHandle) {
+        let entry = self.declare(meta, RangeTable::Values { range });
+        (entry.columns[0], entry.loaded.clone())
+    }
+
+    /// The tag and value columns of the values up to `num_bits` bits.
+    pub(crate) fn tagged<F: Field>(
+        &mut self,
+        meta: &mut ConstraintSystem<F>,
+        num_bits: usize,
+    ) -> (TableColumn, TableColumn, TableHandle) {
+        let entry = self.declare(meta, RangeTable::Tagged { num_bits });
+        (entry.columns[0], entry.columns[1], entry.loaded.clone())
+    }
+
+    fn declare<F: Field>(&mut self, meta: &mut ConstraintSystem<F>, table: RangeTable) -> &Entry {
+        let index = match self.entries.iter().position(|entry| entry.table == table) {
+            Some(index) => index,
+            None => {
+                let num_columns = match table {
+                    RangeTable::Values { .. } => 1,
+                    RangeTable::Tagged { .. } => 2,
+                };
+                self.entries.push(Entry {
+                    table,
+                    columns: (0..num_columns)
+                        .map(|_| meta.lookup_table_column())
+                        .collect(),
+                    loaded: TableHandle(Rc::new(Cell::new(false))),
+                });
+                self.entries.len() - 1
+            }
+        };
+        &self.entries[index]
+    }
+
+    /// Load every declared table. Fails if they are already loaded.
+    pub fn load<F: Field>(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        if self.entries.iter().any(|entry| entry.loaded.0.get()) {
+            return Err(Error::Synthesis);
+        }
+
+        for entry in self.entries.iter() {
+            match entry.table {
+                RangeTable::Values { range } => load_values(layouter, entry.columns[0], range)?,
+                RangeTable::Tagged { num_bits } => {
+                    load_tagged(layouter, entry.columns[0], entry.columns[1], num_bits)?
+                }
+            }
+            entry.loaded.0.set(true);
+        }
+        Ok(())
+    }
+
+    /// Fails if a declared table was never loaded. The tables are marked as
+    /// unloaded again, since floor planners like `V1` synthesize the circuit
+    /// more than once with clones of the same config.
+    pub fn finish(&self) -> Result<(), Error> {
+        let loaded = self.entries.iter().all(|entry| entry.loaded.0.get());
+        for entry in self.entries.iter() {
+            entry.loaded.0.set(false);
+        }
+
+        if loaded {
+            Ok(())
+        } else {
+            Err(Error::Synthesis)
+        }
+    }
+}
+
+fn load_values<F: Field>(
+    layouter: &mut impl Layouter<F>,
+    column: TableColumn,
+    range: usize,
+) -> Result<(), Error> {
+    layouter.assign_table(
+        || "load range-check table",
+        |mut table| {
+            for (offset, value) in (0..range).enumerate() {
+                table.assign_cell(
+                    || "value",
+                    column,
+                    offset,
+                    || Value::known(F::from(value as u64)),
+                )?;
+            }
+
+            Ok(())
+        },
+    )
+}
+
+fn load_tagged<F: Field>(
+    layouter: &mut impl Layouter<F>,
+    tag: TableColumn,
+    value: TableColumn,
+    num_bits: usize,
+) -> Result<(), Error> {
+    layouter.assign_table(
+        || "load range-check table with num_bits",
+        |mut table| {
+            // | offset | num_bits | value    |
+            // | 0      | 1        | 0        |
+            // | 1      | 1        | 1        | 1 << 0 = 1 .. 1 << 1 = 10
+            // | 2      | 2        | 2 = 10   | 1 << 1 = 10 .. 1 << 2 = 100
+            // | 3      | 2        | 3 = 11   |
+            // | 4      | 3        | 4 = 100  | 1 << 2 = 100 .. 1 << 3 = 1000
+            // ...
+            let rows = std::iter::once((1, 0)).chain(
+                (1..=num_bits).flat_map(|n| ((1u64 << (n - 1))..(1u64 << n)).map(move |v| (n, v))),
+            );
+            for (offset, (n, v)) in rows.enumerate() {
+                table.assign_cell(
+                    || "assign num_bits",
+                    tag,
+                    offset,
+                    || Value::known(F::from(n as u64)),
+                )?;
+                table.assign_cell(
+                    || "assign value",
+                    value,
+                    offset,
+                    || Value::known(F::from(v)),
+                )?;
+            }
+
+            Ok(())
+        },
+    )
+}
@@ -0,0 +1,323 @@
+use crate::backend::{
+    circuit::{floor_planner::V1, Layouter, Value},
+    plonk::{Assigned, Circuit, Column, ConstraintSystem, Error, Instance},
+    Field,
+};
+use crate::chips::comparison::{ComparisonChip, ComparisonConfig};
+use crate::chips::range_check_4::ShortRangeCheck;
+use crate::chips::table_registry::TableRegistry;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ComparisonOp {
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    AssertLt,
+    AssertLe,
+    AssertGt,
+    AssertGe,
+}
+
+#[derive(Debug, Clone)]
+pub struct ComparisonCircuitConfig<
+    F: Field,
+    const N: usize,
+    const NUM_BITS: usize,
+    const RANGE: usize,
+> {
+    comparison: ComparisonConfig<F, N, NUM_BITS, RANGE>,
+    instance: Column<Instance>,
+    tables: TableRegistry,
+}
+
+/// Range-checks `a` and `b` to N bits and compares them with `op`. The boolean
+/// result of `Lt`, `Le`, `Gt` and `Ge` is exposed as the only public input, the
+/// `Assert*` ops have none.
+#[derive(Clone)]
+pub struct ComparisonCircuit<F: Field, const N: usize, const NUM_BITS: usize, const RANGE: usize> {
+    pub a: Value<Assigned<F>>,
+    pub b: Value<Assigned<F>>,
+    pub op: ComparisonOp,
+}
+
+impl<F: Field, const N: usize, const NUM_BITS: usize, const RANGE: usize> Circuit<F>
+    for ComparisonCircuit<F, N, NUM_BITS, RANGE>
+{
+    type Config = ComparisonCircuitConfig<F, N, NUM_BITS, RANGE>;
+    type FloorPlanner = V1;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            a: Value::unknown(),
+            b: Value::unknown(),
+            op: self.op,
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        // Fixed column for constants
+        let constants = meta.fixed_column();
+        meta.enable_constant(constants);
+
+        let advice = [(); 4].map(|_| meta.advice_column());
+        let offset = meta.fixed_column();
+        let instance = meta.instance_column();
+        meta.enable_equality(instance);
+        let mut tables = TableRegistry::new();
+
+        ComparisonCircuitConfig {
+            comparison: ComparisonChip::configure(meta, advice, offset, &mut tables),
+            instance,
+            tables,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        config.tables.load(&mut layouter)?;
+
+        // The comparison needs inputs of at most N bits
+        let inputs = config.comparison.decompose.assign_batch(
+            layouter.namespace(|| "Witness inputs"),
+            &[self.a, self.b],
+            N,
+            ShortRangeCheck::Shifted,
+        )?;
+        let a = inputs[0].range_constrained().unwrap();
+        let b = inputs[1].range_constrained().unwrap();
+
+        let chip = ComparisonChip::construct(config.comparison);
+        let compare = layouter.namespace(|| "compare");
+
+        let out = match self.op {
+            ComparisonOp::Lt => Some(chip.lt(compare, &a, &b)?),
+            ComparisonOp::Le => Some(chip.le(compare, &a, &b)?),
+            ComparisonOp::Gt => Some(chip.gt(compare, &a, &b)?),
+            ComparisonOp::Ge => Some(chip.ge(compare, &a, &b)?),
+            ComparisonOp::AssertLt => {
+                chip.assert_lt(compare, &a, &b)?;
+                None
+            }
+            ComparisonOp::AssertLe => {
+                chip.assert_le(compare, &a, &b)?;
+                None
+            }
+            ComparisonOp::AssertGt => {
+                chip.assert_gt(compare, &a, &b)?;
+                None
+            }
+            ComparisonOp::AssertGe => {
+                chip.assert_ge(compare, &a, &b)?;
+                None
+            }
+        };
+        if let Some(out) = out {
+            layouter.constrain_instance(out.cell(), config.instance, 0)?;
+        }
+
+        config.tables.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ComparisonCircuit, ComparisonOp};
+    use crate::backend::{
+        circuit::{floor_planner::V1, Layouter, Value},
+        dev::MockProver,
+        plonk::{Assigned, Circuit, ConstraintSystem, Error},
+        Fp,
+    };
+    use crate::chips::comparison::ComparisonChip;
+    use crate::chips::range_check_4::ShortRangeCheck;
+    use crate::circuits::under_constrained::assert_constrained;
+
+    const K: u32 = 9;
+    const N: usize = 16;
+    const NUM_BITS: usize = 8;
+    const RANGE: usize = 256;
+
+    type Compare = ComparisonCircuit<Fp, N, NUM_BITS, RANGE>;
+
+    fn circuit(a: u64, b: u64, op: ComparisonOp) -> Compare {
+        Compare {
+            a: Value::known(Assigned::from(Fp::from(a))),
+            b: Value::known(Assigned::from(Fp::from(b))),
+            op,
+        }
+    }
+
+    fn verify(a: u64, b: u64, op: ComparisonOp, public: Vec<Fp>) -> bool {
+        // the assert variants leave the instance column empty
+        MockProver::run(K, &circuit(a, b, op), vec![public])
+            .unwrap()
+            .verify()
+            .is_ok()
+    }
+
+    const PAIRS: [(u64, u64); 8] = [
+        (0, 0),
+        (0, 1),
+        (1, 0),
+        (5, 5),
+        (41, 42),
+        (42, 41),
+        (0, (1 << N) - 1),
+        ((1 << N) - 1, 0),
+    ];
+
+    #[test]
+    fn test_comparison() {
+        let ops: [(ComparisonOp, fn(u64, u64) -> bool); 4] = [
+            (ComparisonOp::Lt, |a, b| a < b),
+            (ComparisonOp::Le, |a, b| a <= b),
+            (ComparisonOp::Gt, |a, b| a > b),
+            (ComparisonOp::Ge, |a, b| a >= b),
+        ];
+
+        for (a, b) in PAIRS {
+            for (op, expected) in ops {
+                let result = Fp::from(expected(a, b) as u64);
+                assert!(verify(a, b, op, vec![result]), "{:?}({}, {})", op, a, b);
+
+                // the other boolean must not verify
+                let wrong = Fp::one() - result;
+                assert!(!verify(a, b, op, vec![wrong]), "{:?}({}, {})", op, a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn test_comparison_assert() {
+        let ops: [(ComparisonOp, fn(u64, u64) -> bool); 4] = [
+            (ComparisonOp::AssertLt, |a, b| a < b),
+            (ComparisonOp::AssertLe, |a, b| a <= b),
+            (ComparisonOp::AssertGt, |a, b| a > b),
+            (ComparisonOp::AssertGe, |a, b| a >= b),
+        ];
+
+        for (a, b) in PAIRS {
+            for (op, expected) in ops {
+                assert_eq!(
+                    verify(a, b, op, vec![]),
+                    expected(a, b),
+                    "{:?}({}, {})",
+                    op,
+                    a,
+                    b
+                );
+            }
+        }
+    }
+
+    // p - 1 is "less than" 0 in b - a - 1 = 0 mod p, unless the inputs are
+    // range-checked first
+    #[test]
+    fn test_comparison_rejects_wrapped_input() {
+        let circuit = |a: Fp, b: Fp, op| Compare {
+            a: Value::known(Assigned::from(a)),
+            b: Value::known(Assigned::from(b)),
+            op,
+        };
+        let verify = |circuit: Compare, public| {
+            MockProver::run(K, &circuit, vec![public])
+                .unwrap()
+                .verify()
+                .is_ok()
+        };
+
+        let minus_one = -Fp::one();
+        for (op, public) in [
+            (ComparisonOp::AssertLt, vec![]),
+            (ComparisonOp::AssertLe, vec![]),
+            (ComparisonOp::Lt, vec![Fp::one()]),
+            (ComparisonOp::Lt, vec![Fp::zero()]),
+        ] {
+            assert!(!verify(circuit(minus_one, Fp::zero(), op), public.clone()));
+            assert!(!verify(circuit(Fp::zero(), minus_one, op), public));
+        }
+    }
+
+    // Compares inputs range-checked to more than N bits
+    struct WideInputCircuit;
+
+    impl Circuit<Fp> for WideInputCircuit {
+        type Config = <Compare as Circuit<Fp>>::Config;
+        type FloorPlanner = V1;
+
+        fn without_witnesses(&self) -> Self {
+            Self
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            Compare::configure(meta)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            config.tables.load(&mut layouter)?;
+
+            let inputs = config.comparison.decompose.assign_batch(
+                layouter.namespace(|| "Witness inputs"),
+                &[Value::known(Assigned::from(Fp::from(1 << N)))],
+                N + NUM_BITS,
+                ShortRangeCheck::Shifted,
+            )?;
+            let a = inputs[0].range_constrained().unwrap();
+
+            let chip = ComparisonChip::construct(config.comparison);
+            chip.assert_lt(layouter.namespace(|| "compare"), &a, &a)?;
+
+            config.tables.finish()
+        }
+    }
+
+    #[test]
+    fn test_comparison_rejects_wide_input() {
+        assert!(matches!(
+            MockProver::run(K, &WideInputCircuit, vec![vec![]]),
+            Err(Error::Synthesis)
+        ));
+    }
+
+    #[test]
+    fn comparison_constrained() {
+        assert_constrained(
+            K,
+            &circuit(3, 5, ComparisonOp::Lt),
+            vec![vec![Fp::one()]],
+            &[],
+        );
+        assert_constrained(K, &circuit(3, 5, ComparisonOp::AssertLt), vec![vec![]], &[]);
+    }
+
+    #[cfg(feature = "dev-graph")]
+    #[test]
+    fn plot_comparison() {
+        use plotters::{backend::BitMapBackend, drawing::IntoDrawingArea, style::WHITE};
+        use std::path::Path;
+
+        let filename = Path::new("./devgraphs/comparison-layout.png");
+        let title = "Comparison Layout";
+
+        let root = BitMapBackend::new(filename, (1024, 3096)).into_drawing_area();
+        root.fill(&WHITE).unwrap();
+        let root = root.titled(title, ("sans-serif", 60)).unwrap();
+
+        let circuit = Compare {
+            a: Value::unknown(),
+            b: Value::unknown(),
+            op: ComparisonOp::Lt,
+        };
+        crate::backend::dev::CircuitLayout::default()
+            .render(K, &circuit, &root)
+            .unwrap();
+    }
+}
@@ -1,17 +1,49 @@
-use halo2_proofs::{arithmetic::Field, circuit::*, plonk::*};
 use std::marker::PhantomData;
 
+use crate::backend::{circuit::*, plonk::*, Field};
 use crate::chips::fib_1::{FibonacciChip, FibonacciConfig};
+use crate::circuits::utils::{check_fibonacci_instance, min_k};
+
+/// Proves `F(n)` given `F(1)` and `F(2)`, with the instance column laid out as
+/// `[F(1), F(2), F(n)]`.
+pub struct FibonacciCircuit1<F> {
+    // index of the term exposed as the public output
+    n: usize,
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field> FibonacciCircuit1<F> {
+    pub const NAME: &str = "fib_1";
+
+    pub fn new(n: usize) -> Self {
+        assert!(n >= 3, "fib_1 needs n >= 3, got {}", n);
+
+        Self {
+            n,
+            _marker: PhantomData,
+        }
+    }
 
-#[derive(Default)]
-struct FibonacciCircuit1<F>(PhantomData<F>);
+    // the first row computes F(3), every following row computes one more term
+    pub fn num_rows(&self) -> usize {
+        self.n - 2
+    }
+
+    pub fn k(&self) -> u32 {
+        min_k::<F, Self>(self.num_rows())
+    }
+
+    pub fn check_instance(&self, instance: &[F]) -> Result<(), Error> {
+        check_fibonacci_instance(instance, self.n)
+    }
+}
 
 impl<F: Field> Circuit<F> for FibonacciCircuit1<F> {
     type Config = FibonacciConfig;
     type FloorPlanner = SimpleFloorPlanner;
 
     fn without_witnesses(&self) -> Self {
-        Self::default()
+        Self::new(self.n)
     }
 
     fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
@@ -38,7 +70,7 @@ impl<F: Field> Circuit<F> for FibonacciCircuit1<F> {
         let (_, mut prev_b, mut prev_c) =
             chip.assign_first_row(layouter.namespace(|| "first row"))?;
 
-        for _i in 3..10 {
+        for _i in 3..self.n {
             let c_cell = chip.assign_row(layouter.namespace(|| "next row"), &prev_b, &prev_c)?;
             prev_b = prev_c;
             prev_c = c_cell;
@@ -52,32 +84,56 @@ impl<F: Field> Circuit<F> for FibonacciCircuit1<F> {
 
 #[cfg(test)]
 mod tests {
-    use std::marker::PhantomData;
-
     use super::FibonacciCircuit1;
-    use halo2_proofs::dev::MockProver;
-    use halo2_proofs::halo2curves::bn256::Fr as Fp;
+    use crate::backend::{dev::MockProver, Fp};
+    use crate::circuits::under_constrained::assert_constrained;
 
     #[test]
     fn fib_1() {
-        let k = 4;
-
         let a = Fp::from(1); // F[1]
         let b = Fp::from(1); // F[2]
         let out = Fp::from(55); // F[10]
 
-        let circuit = FibonacciCircuit1(PhantomData);
+        let circuit = FibonacciCircuit1::new(10);
+        let k = circuit.k();
+        assert_eq!(k, 4);
 
         let mut public_input = vec![a, b, out];
+        circuit.check_instance(&public_input).unwrap();
 
         let prover = MockProver::run(k, &circuit, vec![public_input.clone()]).unwrap();
         prover.assert_satisfied();
 
         public_input[2] += Fp::one();
+        assert!(circuit.check_instance(&public_input).is_err());
         let _prover = MockProver::run(k, &circuit, vec![public_input]).unwrap();
         // _prover.assert_satisfied();
     }
 
+    #[test]
+    fn fib_1_variable_n() {
+        // (n, F[n])
+        for (n, out) in [(3, 2), (4, 3), (20, 6765), (50, 12586269025)] {
+            let circuit = FibonacciCircuit1::new(n);
+            let public_input = vec![Fp::from(1), Fp::from(1), Fp::from(out)];
+            circuit.check_instance(&public_input).unwrap();
+
+            let prover = MockProver::run(circuit.k(), &circuit, vec![public_input]).unwrap();
+            prover.assert_satisfied();
+        }
+
+        // wrong number of public inputs
+        let circuit = FibonacciCircuit1::<Fp>::new(10);
+        assert!(circuit.check_instance(&[Fp::from(1), Fp::from(1)]).is_err());
+    }
+
+    #[test]
+    fn fib_1_constrained() {
+        let circuit = FibonacciCircuit1::new(10);
+        let public_input = vec![Fp::from(1), Fp::from(1), Fp::from(55)];
+        assert_constrained(circuit.k(), &circuit, vec![public_input], &[]);
+    }
+
     #[cfg(feature = "dev-graph")]
     #[test]
     fn plot_fib1() {
@@ -91,9 +147,9 @@ mod tests {
         root.fill(&WHITE).unwrap();
         let root = root.titled(title, ("sans-serif", 60)).unwrap();
 
-        let circuit = FibonacciCircuit1::<Fp>(PhantomData);
-        halo2_proofs::dev::CircuitLayout::default()
-            .render(4, &circuit, &root)
+        let circuit = FibonacciCircuit1::<Fp>::new(10);
+        crate::backend::dev::CircuitLayout::default()
+            .render(circuit.k(), &circuit, &root)
             .unwrap();
     }
 }
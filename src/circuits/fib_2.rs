@@ -1,17 +1,49 @@
-use halo2_proofs::{arithmetic::Field, circuit::*, plonk::*};
 use std::marker::PhantomData;
 
+use crate::backend::{circuit::*, plonk::*, Field};
 use crate::chips::fib_2::{FibonacciChip, FibonacciConfig};
+use crate::circuits::utils::{check_fibonacci_instance, min_k};
+
+/// Proves `F(n)` given `F(1)` and `F(2)`, with the instance column laid out as
+/// `[F(1), F(2), F(n)]`.
+pub struct FibonacciCircuit2<F> {
+    // index of the term exposed as the public output
+    n: usize,
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field> FibonacciCircuit2<F> {
+    pub const NAME: &str = "fib_2";
 
-#[derive(Default)]
-struct FibonacciCircuit2<F>(PhantomData<F>);
+    pub fn new(n: usize) -> Self {
+        assert!(n >= 3, "fib_2 needs n >= 3, got {}", n);
+
+        Self {
+            n,
+            _marker: PhantomData,
+        }
+    }
+
+    // row r holds F(r + 1) and F(r + 2), so the last row holds F(n)
+    pub fn num_rows(&self) -> usize {
+        self.n - 1
+    }
+
+    pub fn k(&self) -> u32 {
+        min_k::<F, Self>(self.num_rows())
+    }
+
+    pub fn check_instance(&self, instance: &[F]) -> Result<(), Error> {
+        check_fibonacci_instance(instance, self.n)
+    }
+}
 
 impl<F: Field> Circuit<F> for FibonacciCircuit2<F> {
     type Config = FibonacciConfig;
     type FloorPlanner = SimpleFloorPlanner;
 
     fn without_witnesses(&self) -> Self {
-        Self::default()
+        Self::new(self.n)
     }
 
     fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
@@ -29,8 +61,7 @@ impl<F: Field> Circuit<F> for FibonacciCircuit2<F> {
     ) -> Result<(), Error> {
         let chip = FibonacciChip::construct(config);
 
-        let num_row = 9;
-        let out_cell = chip.assign(layouter.namespace(|| "private out"), num_row)?;
+        let out_cell = chip.assign(layouter.namespace(|| "private out"), self.num_rows())?;
 
         chip.expose_public(layouter.namespace(|| "out"), &out_cell, 2)?;
 
@@ -40,33 +71,154 @@ impl<F: Field> Circuit<F> for FibonacciCircuit2<F> {
 
 #[cfg(test)]
 mod tests {
-    use std::marker::PhantomData;
-
     use super::FibonacciCircuit2;
-    use halo2_proofs::dev::MockProver;
-    use halo2_proofs::halo2curves::bn256::Fr as Fp;
+    use crate::backend::{
+        circuit::{Layouter, SimpleFloorPlanner, Value},
+        dev::MockProver,
+        plonk::{Circuit, ConstraintSystem, Error},
+        Fp,
+    };
+    use crate::chips::fib_2::FibonacciConfig;
+    use crate::circuits::under_constrained::assert_constrained;
+    use crate::circuits::utils::fibonacci;
 
     #[test]
     fn fib_2() {
-        let k = 4;
-
         let a = Fp::from(1); // F[1]
         let b = Fp::from(1); // F[2]
         let out = Fp::from(55); // F[10]
 
-        let circuit = FibonacciCircuit2(PhantomData);
+        let circuit = FibonacciCircuit2::new(10);
+        let k = circuit.k();
+        assert_eq!(k, 4);
 
         let mut public_input = vec![a, b, out];
+        circuit.check_instance(&public_input).unwrap();
 
         let prover = MockProver::run(k, &circuit, vec![public_input.clone()]).unwrap();
         prover.assert_satisfied();
 
         public_input[2] += Fp::one();
+        assert!(circuit.check_instance(&public_input).is_err());
         let _prover = MockProver::run(k, &circuit, vec![public_input]).unwrap();
         // uncomment the following line and the assert will fail
         // _prover.assert_satisfied();
     }
 
+    #[test]
+    fn fib_2_variable_n() {
+        // (n, F[n])
+        for (n, out) in [(3, 2), (4, 3), (20, 6765), (50, 12586269025)] {
+            let circuit = FibonacciCircuit2::new(n);
+            let public_input = vec![Fp::from(1), Fp::from(1), Fp::from(out)];
+            circuit.check_instance(&public_input).unwrap();
+
+            let prover = MockProver::run(circuit.k(), &circuit, vec![public_input]).unwrap();
+            prover.assert_satisfied();
+        }
+    }
+
+    #[test]
+    fn fib_2_constrained() {
+        let circuit = FibonacciCircuit2::new(10);
+        let public_input = vec![Fp::from(1), Fp::from(1), Fp::from(55)];
+        assert_constrained(circuit.k(), &circuit, vec![public_input], &[]);
+    }
+
+    // Lay out the fib_2 table with the given `a` on the rows after the first,
+    // where each `b` is still the sum of the row above
+    struct TableCircuit {
+        a: Vec<Value<Fp>>,
+    }
+
+    impl Circuit<Fp> for TableCircuit {
+        type Config = FibonacciConfig;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                a: vec![Value::unknown(); self.a.len()],
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            FibonacciCircuit2::<Fp>::configure(meta)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            let out = layouter.assign_region(
+                || "entire fibonacci table",
+                |mut region| {
+                    let mut a = region.assign_advice_from_instance(
+                        || "f(0)",
+                        config.instance,
+                        0,
+                        config.advice[0],
+                        0,
+                    )?;
+                    let mut b = region.assign_advice_from_instance(
+                        || "f(1)",
+                        config.instance,
+                        1,
+                        config.advice[1],
+                        0,
+                    )?;
+
+                    for (row, next_a) in self.a.iter().enumerate() {
+                        config.selector.enable(&mut region, row)?;
+                        let c = region.assign_advice(
+                            || "advice",
+                            config.advice[1],
+                            row + 1,
+                            || a.value().copied() + b.value(),
+                        )?;
+                        a = region.assign_advice(|| "a", config.advice[0], row + 1, || *next_a)?;
+                        b = c;
+                    }
+                    Ok(b)
+                },
+            )?;
+
+            layouter.constrain_instance(out.cell(), config.instance, 2)
+        }
+    }
+
+    // Every row of a table with another `a` still satisfies a + b = c, but the
+    // table no longer computes F(n). Mutating a single cell cannot find this, as
+    // every `b` below the changed `a` has to change with it.
+    #[test]
+    fn fib_2_rejects_other_a() {
+        let fib = |i| fibonacci(Fp::from(1), Fp::from(1), i);
+        let verify = |a: &[Fp], out: Fp| {
+            let circuit = TableCircuit {
+                a: a.iter().copied().map(Value::known).collect(),
+            };
+            let public_input = vec![Fp::from(1), Fp::from(1), out];
+            MockProver::run(4, &circuit, vec![public_input])
+                .unwrap()
+                .verify()
+                .is_ok()
+        };
+
+        // row r holds F(r + 1) and F(r + 2), up to F(10) on the last row
+        let honest: Vec<_> = (2..10).map(fib).collect();
+        assert!(verify(&honest, fib(10)));
+
+        let mut other = honest;
+        other[0] = Fp::from(5);
+        let (_, out) = other
+            .iter()
+            .fold((Fp::from(1), Fp::from(1)), |(a, b), next_a| {
+                (*next_a, a + b)
+            });
+        assert_ne!(out, fib(10));
+        assert!(!verify(&other, out));
+    }
+
     #[cfg(feature = "dev-graph")]
     #[test]
     fn plot_fib2() {
@@ -80,9 +232,9 @@ mod tests {
         root.fill(&WHITE).unwrap();
         let root = root.titled(title, ("sans-serif", 60)).unwrap();
 
-        let circuit = FibonacciCircuit2::<Fp>(PhantomData);
-        halo2_proofs::dev::CircuitLayout::default()
-            .render(4, &circuit, &root)
+        let circuit = FibonacciCircuit2::<Fp>::new(10);
+        crate::backend::dev::CircuitLayout::default()
+            .render(circuit.k(), &circuit, &root)
             .unwrap();
     }
 }
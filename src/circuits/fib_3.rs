@@ -1,17 +1,49 @@
-use halo2_proofs::{arithmetic::Field, circuit::*, plonk::*};
 use std::marker::PhantomData;
 
+use crate::backend::{circuit::*, plonk::*, Field};
 use crate::chips::fib_3::{FibonacciChip, FibonacciConfig};
+use crate::circuits::utils::{check_fibonacci_instance, min_k};
+
+/// Proves `F(n)` given `F(1)` and `F(2)`, with the instance column laid out as
+/// `[F(1), F(2), F(n)]`.
+pub struct FibonacciCircuit3<F> {
+    // index of the term exposed as the public output
+    n: usize,
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field> FibonacciCircuit3<F> {
+    pub const NAME: &str = "fib_3";
+
+    pub fn new(n: usize) -> Self {
+        assert!(n >= 3, "fib_3 needs n >= 3, got {}", n);
+
+        Self {
+            n,
+            _marker: PhantomData,
+        }
+    }
 
-#[derive(Default)]
-struct FibonacciCircuit3<F>(PhantomData<F>);
+    // row r holds F(r + 1)
+    pub fn num_rows(&self) -> usize {
+        self.n
+    }
+
+    pub fn k(&self) -> u32 {
+        min_k::<F, Self>(self.num_rows())
+    }
+
+    pub fn check_instance(&self, instance: &[F]) -> Result<(), Error> {
+        check_fibonacci_instance(instance, self.n)
+    }
+}
 
 impl<F: Field> Circuit<F> for FibonacciCircuit3<F> {
     type Config = FibonacciConfig;
     type FloorPlanner = SimpleFloorPlanner;
 
     fn without_witnesses(&self) -> Self {
-        Self::default()
+        Self::new(self.n)
     }
 
     fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
@@ -28,8 +60,7 @@ impl<F: Field> Circuit<F> for FibonacciCircuit3<F> {
     ) -> Result<(), Error> {
         let chip = FibonacciChip::construct(config);
 
-        let num_row = 10;
-        let out_cell = chip.assign(layouter.namespace(|| "private out"), num_row)?;
+        let out_cell = chip.assign(layouter.namespace(|| "private out"), self.num_rows())?;
 
         chip.expose_public(layouter.namespace(|| "out"), &out_cell, 2)?;
 
@@ -39,35 +70,54 @@ impl<F: Field> Circuit<F> for FibonacciCircuit3<F> {
 
 #[cfg(test)]
 mod tests {
-    use std::marker::PhantomData;
-
-    use halo2_proofs::dev::MockProver;
-    use halo2_proofs::halo2curves::bn256::Fr as Fp;
-
+    use crate::backend::{dev::MockProver, Fp};
     use crate::circuits::fib_3::FibonacciCircuit3;
+    use crate::circuits::under_constrained::assert_constrained;
     // use crate::circuits::utils::plot;
 
     #[test]
     fn fib_3() {
-        let k = 4;
-
         let a = Fp::from(1); // F[1]
         let b = Fp::from(1); // F[2]
         let out = Fp::from(55); // F[10]
 
-        let circuit = FibonacciCircuit3(PhantomData);
+        let circuit = FibonacciCircuit3::new(10);
+        let k = circuit.k();
+        assert_eq!(k, 4);
 
         let mut public_input = vec![a, b, out];
+        circuit.check_instance(&public_input).unwrap();
 
         let prover = MockProver::run(k, &circuit, vec![public_input.clone()]).unwrap();
         prover.assert_satisfied();
 
         public_input[2] += Fp::one();
+        assert!(circuit.check_instance(&public_input).is_err());
         let _prover = MockProver::run(k, &circuit, vec![public_input]).unwrap();
         // uncomment the following line and the assert will fail
         // _prover.assert_satisfied();
     }
 
+    #[test]
+    fn fib_3_variable_n() {
+        // (n, F[n])
+        for (n, out) in [(3, 2), (4, 3), (20, 6765), (50, 12586269025)] {
+            let circuit = FibonacciCircuit3::new(n);
+            let public_input = vec![Fp::from(1), Fp::from(1), Fp::from(out)];
+            circuit.check_instance(&public_input).unwrap();
+
+            let prover = MockProver::run(circuit.k(), &circuit, vec![public_input]).unwrap();
+            prover.assert_satisfied();
+        }
+    }
+
+    #[test]
+    fn fib_3_constrained() {
+        let circuit = FibonacciCircuit3::new(10);
+        let public_input = vec![Fp::from(1), Fp::from(1), Fp::from(55)];
+        assert_constrained(circuit.k(), &circuit, vec![public_input], &[]);
+    }
+
     #[cfg(feature = "dev-graph")]
     #[test]
     fn plot_fib3() {
@@ -81,9 +131,9 @@ mod tests {
         root.fill(&WHITE).unwrap();
         let root = root.titled(title, ("sans-serif", 60)).unwrap();
 
-        let circuit = FibonacciCircuit3::<Fp>(PhantomData);
-        halo2_proofs::dev::CircuitLayout::default()
-            .render(4, &circuit, &root)
+        let circuit = FibonacciCircuit3::<Fp>::new(10);
+        crate::backend::dev::CircuitLayout::default()
+            .render(circuit.k(), &circuit, &root)
             .unwrap();
     }
 
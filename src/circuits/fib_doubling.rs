@@ -0,0 +1,173 @@
+use std::marker::PhantomData;
+
+use crate::backend::{circuit::*, plonk::*, Field};
+use crate::chips::fib_doubling::{FibDoublingChip, FibDoublingConfig};
+use crate::circuits::utils::{fibonacci, min_k};
+
+/// Proves `F(n)` in `num_bits + 1` rows with the fast doubling identities, where
+/// `n < 2^num_bits` is a private witness. The instance column is `[n, F(n)]`.
+pub struct FibDoublingCircuit<F> {
+    n: Value<u64>,
+    num_bits: usize,
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field + From<u64>> FibDoublingCircuit<F> {
+    pub fn new(n: Value<u64>, num_bits: usize) -> Self {
+        assert!(num_bits > 0 && num_bits <= 64);
+
+        Self {
+            n,
+            num_bits,
+            _marker: PhantomData,
+        }
+    }
+
+    // one row per bit plus the final (F(n), F(n + 1)) row
+    pub fn num_rows(&self) -> usize {
+        self.num_bits + 1
+    }
+
+    pub fn k(&self) -> u32 {
+        min_k::<F, Self>(self.num_rows())
+    }
+
+    /// Expected instance `[n, F(n)]` with F(0) = 0 and F(1) = 1.
+    pub fn instance(n: u64) -> Vec<F> {
+        let out = match n {
+            0 => F::from(0),
+            n => fibonacci(F::from(1), F::from(1), n as usize),
+        };
+        vec![F::from(n), out]
+    }
+}
+
+impl<F: Field + From<u64>> Circuit<F> for FibDoublingCircuit<F> {
+    type Config = FibDoublingConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::new(Value::unknown(), self.num_bits)
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [
+            meta.advice_column(),
+            meta.advice_column(),
+            meta.advice_column(),
+            meta.advice_column(),
+        ];
+        let instance = meta.instance_column();
+        let constant = meta.fixed_column();
+        meta.enable_constant(constant);
+
+        FibDoublingChip::configure(meta, advice, instance)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = FibDoublingChip::construct(config);
+
+        let (n_cell, out_cell) =
+            chip.assign(layouter.namespace(|| "doubling"), self.n, self.num_bits)?;
+
+        chip.expose_public(layouter.namespace(|| "n"), &n_cell, 0)?;
+        chip.expose_public(layouter.namespace(|| "out"), &out_cell, 1)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FibDoublingCircuit;
+    use crate::backend::{circuit::Value, dev::MockProver, Fp};
+    use crate::circuits::fib_1::FibonacciCircuit1;
+    use crate::circuits::under_constrained::assert_constrained;
+
+    #[test]
+    fn fib_doubling() {
+        for n in [0, 1, 2, 10, 63] {
+            let circuit = FibDoublingCircuit::<Fp>::new(Value::known(n), 6);
+            let mut public_input = FibDoublingCircuit::<Fp>::instance(n);
+
+            let prover =
+                MockProver::run(circuit.k(), &circuit, vec![public_input.clone()]).unwrap();
+            prover.assert_satisfied();
+
+            public_input[1] += Fp::one();
+            let prover = MockProver::run(circuit.k(), &circuit, vec![public_input]).unwrap();
+            assert!(prover.verify().is_err());
+        }
+    }
+
+    // same outputs as fib_1 with the public input [1, 1, F(n)]
+    #[test]
+    fn fib_doubling_matches_fib_1() {
+        for n in 3..=40 {
+            let fib = FibonacciCircuit1::<Fp>::new(n);
+            let doubling = FibDoublingCircuit::<Fp>::new(Value::known(n as u64), 8);
+            let public_input = FibDoublingCircuit::<Fp>::instance(n as u64);
+
+            let fib_input = vec![Fp::from(1), Fp::from(1), public_input[1]];
+            fib.check_instance(&fib_input).unwrap();
+            MockProver::run(fib.k(), &fib, vec![fib_input])
+                .unwrap()
+                .assert_satisfied();
+
+            MockProver::run(doubling.k(), &doubling, vec![public_input])
+                .unwrap()
+                .assert_satisfied();
+        }
+    }
+
+    // 65 rows for any n < 2^64
+    #[test]
+    fn fib_doubling_large_n() {
+        let n = 100_003;
+        let circuit = FibDoublingCircuit::<Fp>::new(Value::known(n), 64);
+        assert_eq!(circuit.k(), 7);
+
+        let public_input = FibDoublingCircuit::<Fp>::instance(n);
+        let prover = MockProver::run(circuit.k(), &circuit, vec![public_input]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    // n has to fit in num_bits
+    #[test]
+    fn fib_doubling_n_out_of_range() {
+        let circuit = FibDoublingCircuit::<Fp>::new(Value::known(64), 6);
+        let public_input = FibDoublingCircuit::<Fp>::instance(64);
+        let prover = MockProver::run(circuit.k(), &circuit, vec![public_input]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn fib_doubling_constrained() {
+        let circuit = FibDoublingCircuit::<Fp>::new(Value::known(10), 6);
+        let public_input = FibDoublingCircuit::<Fp>::instance(10);
+        assert_constrained(circuit.k(), &circuit, vec![public_input], &[]);
+    }
+
+    #[cfg(feature = "dev-graph")]
+    #[test]
+    fn plot_fib_doubling() {
+        use plotters::prelude::*;
+        use std::path::Path;
+
+        let filename = Path::new("./devgraphs/fib-doubling-layout.png");
+        let title = "Fibonacci Doubling Layout";
+
+        let root = BitMapBackend::new(filename, (1024, 3096)).into_drawing_area();
+        root.fill(&WHITE).unwrap();
+        let root = root.titled(title, ("sans-serif", 60)).unwrap();
+
+        let circuit = FibDoublingCircuit::<Fp>::new(Value::unknown(), 8);
+        crate::backend::dev::CircuitLayout::default()
+            .render(circuit.k(), &circuit, &root)
+            .unwrap();
+    }
+}
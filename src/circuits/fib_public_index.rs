@@ -0,0 +1,174 @@
+use std::marker::PhantomData;
+
+use crate::backend::{circuit::*, plonk::*, Field};
+use crate::chips::fib_public_index::{FibonacciPublicIndexChip, FibonacciPublicIndexConfig};
+use crate::circuits::utils::{fibonacci, min_k};
+
+/// Proves `F(n)` given `F(1)` and `F(2)` for any public `1 <= n <= N_MAX`, with
+/// the instance column laid out as `[F(1), F(2), n, F(n)]`. The layout only
+/// depends on `N_MAX`, so a single verifying key covers every `n`.
+pub struct FibonacciPublicIndexCircuit<F, const N_MAX: usize> {
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field + From<u64>, const N_MAX: usize> FibonacciPublicIndexCircuit<F, N_MAX> {
+    pub fn new() -> Self {
+        assert!(N_MAX > 0, "fib_public_index needs N_MAX > 0");
+
+        Self {
+            _marker: PhantomData,
+        }
+    }
+
+    // one row per candidate index plus the final row
+    pub fn num_rows(&self) -> usize {
+        N_MAX + 1
+    }
+
+    pub fn k(&self) -> u32 {
+        min_k::<F, Self>(self.num_rows())
+    }
+
+    pub fn check_instance(&self, instance: &[F]) -> Result<(), Error> {
+        if instance.len() != 4 {
+            return Err(Error::InvalidInstances);
+        }
+
+        let n = (1..=N_MAX)
+            .find(|n| F::from(*n as u64) == instance[2])
+            .ok_or(Error::InvalidInstances)?;
+        if fibonacci(instance[0], instance[1], n) != instance[3] {
+            return Err(Error::InvalidInstances);
+        }
+
+        Ok(())
+    }
+}
+
+impl<F: Field + From<u64>, const N_MAX: usize> Default for FibonacciPublicIndexCircuit<F, N_MAX> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: Field + From<u64>, const N_MAX: usize> Circuit<F>
+    for FibonacciPublicIndexCircuit<F, N_MAX>
+{
+    type Config = FibonacciPublicIndexConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::new()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [
+            meta.advice_column(),
+            meta.advice_column(),
+            meta.advice_column(),
+            meta.advice_column(),
+            meta.advice_column(),
+            meta.advice_column(),
+        ];
+        let index = meta.fixed_column();
+        let instance = meta.instance_column();
+        let constant = meta.fixed_column();
+        meta.enable_constant(constant);
+
+        FibonacciPublicIndexChip::configure(meta, advice, index, instance)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = FibonacciPublicIndexChip::construct(config);
+
+        let out_cell = chip.assign(layouter.namespace(|| "table"), N_MAX, 2)?;
+
+        chip.expose_public(layouter.namespace(|| "out"), &out_cell, 3)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FibonacciPublicIndexCircuit;
+    use crate::backend::{dev::MockProver, Fp};
+    use crate::circuits::under_constrained::assert_constrained;
+    use crate::circuits::utils::fibonacci;
+
+    const N_MAX: usize = 20;
+
+    fn public_input(n: u64) -> Vec<Fp> {
+        let out = fibonacci(Fp::from(1), Fp::from(1), n.max(1) as usize);
+        vec![Fp::from(1), Fp::from(1), Fp::from(n), out]
+    }
+
+    #[test]
+    fn fib_public_index() {
+        let circuit = FibonacciPublicIndexCircuit::<Fp, N_MAX>::new();
+        let k = circuit.k();
+
+        for n in 1..=N_MAX as u64 {
+            let mut public_input = public_input(n);
+            circuit.check_instance(&public_input).unwrap();
+
+            let prover = MockProver::run(k, &circuit, vec![public_input.clone()]).unwrap();
+            prover.assert_satisfied();
+
+            public_input[3] += Fp::one();
+            assert!(circuit.check_instance(&public_input).is_err());
+            let prover = MockProver::run(k, &circuit, vec![public_input]).unwrap();
+            assert!(prover.verify().is_err());
+        }
+    }
+
+    // n has to be one of the indices of the table
+    #[test]
+    fn fib_public_index_out_of_range() {
+        let circuit = FibonacciPublicIndexCircuit::<Fp, N_MAX>::new();
+
+        for n in [0, N_MAX as u64 + 1] {
+            let public_input = public_input(n);
+            assert!(circuit.check_instance(&public_input).is_err());
+
+            let prover = MockProver::run(circuit.k(), &circuit, vec![public_input]).unwrap();
+            assert!(prover.verify().is_err());
+        }
+    }
+
+    #[test]
+    fn fib_public_index_constrained() {
+        let circuit = FibonacciPublicIndexCircuit::<Fp, 10>::new();
+        // `inv` only appears in `(n - index) * inv`, which vanishes on the row
+        // where `index = n`
+        assert_constrained(
+            circuit.k(),
+            &circuit,
+            vec![public_input(5)],
+            &[("fibonacci public index", "inv")],
+        );
+    }
+
+    #[cfg(feature = "dev-graph")]
+    #[test]
+    fn plot_fib_public_index() {
+        use plotters::prelude::*;
+        use std::path::Path;
+
+        let filename = Path::new("./devgraphs/fib-public-index-layout.png");
+        let title = "Fibonacci Public Index Layout";
+
+        let root = BitMapBackend::new(filename, (1024, 3096)).into_drawing_area();
+        root.fill(&WHITE).unwrap();
+        let root = root.titled(title, ("sans-serif", 60)).unwrap();
+
+        let circuit = FibonacciPublicIndexCircuit::<Fp, 10>::new();
+        crate::backend::dev::CircuitLayout::default()
+            .render(circuit.k(), &circuit, &root)
+            .unwrap();
+    }
+}
@@ -0,0 +1,205 @@
+use std::marker::PhantomData;
+
+use crate::backend::{
+    circuit::{Layouter, SimpleFloorPlanner},
+    plonk::{Assigned, Circuit, ConstraintSystem, Error},
+    Field,
+};
+use crate::chips::fib_1::{FibonacciChip, FibonacciConfig};
+use crate::chips::range_check_4::{RangeCheckDecomposeConfig, ShortRangeCheck};
+use crate::chips::table_registry::TableRegistry;
+use crate::circuits::utils::{check_fibonacci_instance, min_k};
+
+#[derive(Debug, Clone)]
+pub struct FibRangeCheckConfig<F: Field, const NUM_BITS: usize, const RANGE: usize> {
+    fibonacci: FibonacciConfig,
+    decompose: RangeCheckDecomposeConfig<F, NUM_BITS, RANGE>,
+    tables: TableRegistry,
+}
+
+/// Proves `F(n)` given `F(1)` and `F(2)` as `FibonacciCircuit1` does, and
+/// constrains `F(n)` to `num_bits` bits with the `range_check_4` chip. The
+/// instance column is laid out as `[F(1), F(2), F(n)]`.
+pub struct FibRangeCheckCircuit<F, const NUM_BITS: usize, const RANGE: usize> {
+    // index of the term exposed as the public output
+    n: usize,
+    num_bits: usize,
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field, const NUM_BITS: usize, const RANGE: usize> FibRangeCheckCircuit<F, NUM_BITS, RANGE> {
+    pub fn new(n: usize, num_bits: usize) -> Self {
+        assert!(n >= 3, "fib_range_check needs n >= 3, got {}", n);
+
+        Self {
+            n,
+            num_bits,
+            _marker: PhantomData,
+        }
+    }
+
+    // the Fibonacci rows, F(n) copied into the decomposition and its running
+    // sum share the same advice columns, while the table needs RANGE rows
+    pub fn num_rows(&self) -> usize {
+        let decompose = RangeCheckDecomposeConfig::<F, NUM_BITS, RANGE>::num_rows(
+            self.num_bits,
+            ShortRangeCheck::Shifted,
+        );
+        (self.n - 2 + 1 + decompose).max(RANGE)
+    }
+
+    pub fn k(&self) -> u32 {
+        min_k::<F, Self>(self.num_rows())
+    }
+
+    pub fn check_instance(&self, instance: &[F]) -> Result<(), Error> {
+        check_fibonacci_instance(instance, self.n)
+    }
+}
+
+impl<F: Field, const NUM_BITS: usize, const RANGE: usize> Circuit<F>
+    for FibRangeCheckCircuit<F, NUM_BITS, RANGE>
+{
+    type Config = FibRangeCheckConfig<F, NUM_BITS, RANGE>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::new(self.n, self.num_bits)
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        // Fixed column for constants
+        let constants = meta.fixed_column();
+        meta.enable_constant(constants);
+
+        let advice = [(); 3].map(|_| meta.advice_column());
+        let instance = meta.instance_column();
+        let mut tables = TableRegistry::new();
+
+        FibRangeCheckConfig {
+            fibonacci: FibonacciChip::configure(meta, advice, instance),
+            decompose: RangeCheckDecomposeConfig::configure(
+                meta,
+                advice[0],
+                advice[1],
+                &mut tables,
+            ),
+            tables,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        config.tables.load(&mut layouter)?;
+
+        let chip = FibonacciChip::construct(config.fibonacci);
+
+        let (_, mut prev_b, mut prev_c) =
+            chip.assign_first_row(layouter.namespace(|| "first row"))?;
+
+        for _i in 3..self.n {
+            let c_cell = chip.assign_row(layouter.namespace(|| "next row"), &prev_b, &prev_c)?;
+            prev_b = prev_c;
+            prev_c = c_cell;
+        }
+
+        chip.expose_public(layouter.namespace(|| "out"), &prev_c, 2)?;
+
+        // The decomposition works on `Assigned<F>` cells, so F(n) is witnessed
+        // again and tied to the original cell
+        let out = layouter.assign_region(
+            || "Copy F(n)",
+            |mut region| {
+                let out = region.assign_advice(
+                    || "F(n)",
+                    config.decompose.running_sum,
+                    0,
+                    || prev_c.value().map(|v| Assigned::from(*v)),
+                )?;
+                region.constrain_equal(prev_c.cell(), out.cell())?;
+                Ok(out)
+            },
+        )?;
+
+        config
+            .decompose
+            .assign(layouter.namespace(|| "Decompose F(n)"), out, self.num_bits)?;
+
+        config.tables.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FibRangeCheckCircuit;
+    use crate::backend::{dev::MockProver, Fp};
+    use crate::circuits::under_constrained::assert_constrained;
+    use crate::circuits::utils::fibonacci;
+
+    const NUM_BITS: usize = 10;
+    const RANGE: usize = 1024;
+
+    type FibRangeCheck = FibRangeCheckCircuit<Fp, NUM_BITS, RANGE>;
+
+    fn verify(n: usize, num_bits: usize, out: Fp) -> bool {
+        let circuit = FibRangeCheck::new(n, num_bits);
+        let public_input = vec![Fp::one(), Fp::one(), out];
+
+        MockProver::run(circuit.k(), &circuit, vec![public_input])
+            .unwrap()
+            .verify()
+            .is_ok()
+    }
+
+    #[test]
+    fn fib_range_check() {
+        // F(50) = 12586269025 is a 34-bit value
+        let n = 50;
+        let out = fibonacci(Fp::one(), Fp::one(), n);
+        assert_eq!(out, Fp::from(12586269025));
+
+        assert!(verify(n, 34, out));
+        assert!(verify(n, 40, out));
+        assert!(!verify(n, 33, out));
+        assert!(!verify(n, 30, out));
+
+        // a wrong output is rejected even when it is in range
+        assert!(!verify(n, 34, out + Fp::one()));
+    }
+
+    #[test]
+    fn fib_range_check_num_rows() {
+        // the table dominates short sequences
+        assert_eq!(FibRangeCheck::new(50, 34).num_rows(), RANGE);
+        assert_eq!(FibRangeCheck::new(50, 34).k(), 11);
+    }
+
+    #[test]
+    fn fib_range_check_constrained() {
+        let circuit = FibRangeCheck::new(10, 10);
+        let public_input = vec![Fp::one(), Fp::one(), Fp::from(55)];
+        assert_constrained(circuit.k(), &circuit, vec![public_input], &[]);
+    }
+
+    #[cfg(feature = "dev-graph")]
+    #[test]
+    fn plot_fib_range_check() {
+        use plotters::prelude::*;
+        use std::path::Path;
+
+        let filename = Path::new("./devgraphs/fib-range-check-layout.png");
+        let title = "Fib Range Check Layout";
+
+        let root = BitMapBackend::new(filename, (1024, 3096)).into_drawing_area();
+        root.fill(&WHITE).unwrap();
+        let root = root.titled(title, ("sans-serif", 60)).unwrap();
+
+        let circuit = FibRangeCheck::new(50, 34);
+        crate::backend::dev::CircuitLayout::default()
+            .render(circuit.k(), &circuit, &root)
+            .unwrap();
+    }
+}
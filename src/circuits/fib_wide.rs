@@ -0,0 +1,146 @@
+use std::marker::PhantomData;
+
+use crate::backend::{circuit::*, plonk::*, Field};
+use crate::chips::fib_wide::{FibWideChip, FibWideConfig};
+use crate::circuits::utils::{check_fibonacci_instance, min_k};
+
+/// Proves `F(n)` given `F(1)` and `F(2)` with `W` steps per row, with the
+/// instance column laid out as `[F(1), F(2), F(n)]`.
+pub struct FibonacciWideCircuit<F, const W: usize> {
+    // index of the term exposed as the public output
+    n: usize,
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field, const W: usize> FibonacciWideCircuit<F, W> {
+    pub fn new(n: usize) -> Self {
+        assert!(n >= 3, "fib_wide needs n >= 3, got {}", n);
+
+        Self {
+            n,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn num_rows(&self) -> usize {
+        FibWideChip::<F, W>::num_rows(self.n)
+    }
+
+    pub fn k(&self) -> u32 {
+        min_k::<F, Self>(self.num_rows())
+    }
+
+    pub fn check_instance(&self, instance: &[F]) -> Result<(), Error> {
+        check_fibonacci_instance(instance, self.n)
+    }
+}
+
+impl<F: Field, const W: usize> Circuit<F> for FibonacciWideCircuit<F, W> {
+    type Config = FibWideConfig<W>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::new(self.n)
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice: Vec<_> = (0..=W).map(|_| meta.advice_column()).collect();
+        let instance = meta.instance_column();
+
+        FibWideChip::configure(meta, &advice, instance)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = FibWideChip::construct(config);
+
+        let out_cell = chip.assign(layouter.namespace(|| "private out"), self.n)?;
+
+        chip.expose_public(layouter.namespace(|| "out"), &out_cell, 2)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FibonacciWideCircuit;
+    use crate::backend::{dev::MockProver, Fp};
+    use crate::circuits::under_constrained::assert_constrained;
+    use crate::circuits::{
+        fib_1::FibonacciCircuit1, fib_2::FibonacciCircuit2, fib_3::FibonacciCircuit3,
+        utils::fibonacci,
+    };
+
+    fn check<const W: usize>(n: usize) {
+        let circuit = FibonacciWideCircuit::<Fp, W>::new(n);
+        let out = fibonacci(Fp::from(1), Fp::from(1), n);
+        let mut public_input = vec![Fp::from(1), Fp::from(1), out];
+        circuit.check_instance(&public_input).unwrap();
+
+        let prover = MockProver::run(circuit.k(), &circuit, vec![public_input.clone()]).unwrap();
+        prover.assert_satisfied();
+
+        public_input[2] += Fp::one();
+        let prover = MockProver::run(circuit.k(), &circuit, vec![public_input]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn fib_wide() {
+        // the output lands on every column of the last row
+        for n in 3..=12 {
+            check::<1>(n);
+            check::<2>(n);
+            check::<3>(n);
+            check::<4>(n);
+        }
+    }
+
+    #[test]
+    fn fib_wide_min_k() {
+        let n = 1000;
+
+        let k_1 = FibonacciCircuit1::<Fp>::new(n).k();
+        let k_2 = FibonacciCircuit2::<Fp>::new(n).k();
+        let k_3 = FibonacciCircuit3::<Fp>::new(n).k();
+        assert_eq!((k_1, k_2, k_3), (10, 10, 10));
+
+        assert_eq!(FibonacciWideCircuit::<Fp, 1>::new(n).k(), 10);
+        assert_eq!(FibonacciWideCircuit::<Fp, 4>::new(n).k(), 8);
+        assert_eq!(FibonacciWideCircuit::<Fp, 8>::new(n).k(), 8);
+        assert_eq!(FibonacciWideCircuit::<Fp, 16>::new(n).k(), 7);
+
+        check::<8>(n);
+        check::<16>(n);
+    }
+
+    #[test]
+    fn fib_wide_constrained() {
+        let circuit = FibonacciWideCircuit::<Fp, 3>::new(10);
+        let public_input = vec![Fp::from(1), Fp::from(1), Fp::from(55)];
+        assert_constrained(circuit.k(), &circuit, vec![public_input], &[]);
+    }
+
+    #[cfg(feature = "dev-graph")]
+    #[test]
+    fn plot_fib_wide() {
+        use plotters::prelude::*;
+        use std::path::Path;
+
+        let filename = Path::new("./devgraphs/fib-wide-layout.png");
+        let title = "Fib Wide Layout";
+
+        let root = BitMapBackend::new(filename, (1024, 3096)).into_drawing_area();
+        root.fill(&WHITE).unwrap();
+        let root = root.titled(title, ("sans-serif", 60)).unwrap();
+
+        let circuit = FibonacciWideCircuit::<Fp, 4>::new(30);
+        crate::backend::dev::CircuitLayout::default()
+            .render(circuit.k(), &circuit, &root)
+            .unwrap();
+    }
+}
@@ -0,0 +1,183 @@
+use std::marker::PhantomData;
+
+use crate::backend::{circuit::*, plonk::*, Field};
+use crate::chips::fibonacci::FibonacciInstructions;
+use crate::circuits::utils::{check_fibonacci_instance, min_k};
+
+/// Proves `F(n)` given `F(1)` and `F(2)` with any chip implementing
+/// `FibonacciInstructions`, with the instance column laid out as
+/// `[F(1), F(2), F(n)]`.
+pub struct FibonacciCircuit<F, C> {
+    // index of the term exposed as the public output
+    n: usize,
+    _marker: PhantomData<(F, C)>,
+}
+
+impl<F: Field, C: FibonacciInstructions<F>> FibonacciCircuit<F, C> {
+    pub fn new(n: usize) -> Self {
+        assert!(n >= 3, "fibonacci needs n >= 3, got {}", n);
+
+        Self {
+            n,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn num_rows(&self) -> usize {
+        C::num_rows(self.n)
+    }
+
+    pub fn k(&self) -> u32 {
+        min_k::<F, Self>(self.num_rows())
+    }
+
+    pub fn check_instance(&self, instance: &[F]) -> Result<(), Error> {
+        check_fibonacci_instance(instance, self.n)
+    }
+}
+
+impl<F: Field, C: FibonacciInstructions<F>> Circuit<F> for FibonacciCircuit<F, C> {
+    type Config = C::Config;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::new(self.n)
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        C::configure_columns(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = C::construct(config);
+
+        let (a_cell, b_cell) = chip.load_initial(layouter.namespace(|| "initial terms"))?;
+        let out_cell = chip.extend(
+            layouter.namespace(|| "private out"),
+            (&a_cell, &b_cell),
+            self.n,
+        )?;
+
+        chip.expose_public(layouter.namespace(|| "out"), &out_cell, 2)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FibonacciCircuit;
+    use crate::backend::{dev::MockProver, plonk::Circuit, Fp};
+    use crate::chips::{fib_1, fib_2, fib_3, fibonacci::FibonacciInstructions};
+    use crate::circuits::under_constrained::assert_constrained;
+    use crate::circuits::{
+        fib_1::FibonacciCircuit1, fib_2::FibonacciCircuit2, fib_3::FibonacciCircuit3,
+        utils::fibonacci,
+    };
+
+    // (F(1), F(2)): Fibonacci, Lucas and arbitrary starting terms
+    const INITIAL: [(u64, u64); 4] = [(1, 1), (2, 1), (0, 0), (7, 3)];
+
+    fn run<C: Circuit<Fp>>(k: u32, circuit: &C, public_input: &[Fp]) -> bool {
+        MockProver::run(k, circuit, vec![public_input.to_vec()])
+            .unwrap()
+            .verify()
+            .is_ok()
+    }
+
+    // accepts exactly the same public outputs as the chip's own circuit
+    fn differential<C: FibonacciInstructions<Fp>, D: Circuit<Fp>>(
+        n: usize,
+        dedicated: &D,
+        dedicated_k: u32,
+        public_input: &[Fp],
+    ) -> bool {
+        let generic = FibonacciCircuit::<Fp, C>::new(n);
+        let accepted = run(generic.k(), &generic, public_input);
+        assert_eq!(accepted, run(dedicated_k, dedicated, public_input));
+        accepted
+    }
+
+    #[test]
+    fn fibonacci_differential() {
+        for n in 3..=30 {
+            for (a, b) in INITIAL {
+                let (a, b) = (Fp::from(a), Fp::from(b));
+                let out = fibonacci(a, b, n);
+
+                for out in [out, out + Fp::one()] {
+                    let public_input = [a, b, out];
+
+                    let circuit_1 = FibonacciCircuit1::<Fp>::new(n);
+                    let circuit_2 = FibonacciCircuit2::<Fp>::new(n);
+                    let circuit_3 = FibonacciCircuit3::<Fp>::new(n);
+                    let (k_1, k_2, k_3) = (circuit_1.k(), circuit_2.k(), circuit_3.k());
+
+                    let accepted = [
+                        differential::<fib_1::FibonacciChip<Fp>, _>(
+                            n,
+                            &circuit_1,
+                            k_1,
+                            &public_input,
+                        ),
+                        differential::<fib_2::FibonacciChip<Fp>, _>(
+                            n,
+                            &circuit_2,
+                            k_2,
+                            &public_input,
+                        ),
+                        differential::<fib_3::FibonacciChip<Fp>, _>(
+                            n,
+                            &circuit_3,
+                            k_3,
+                            &public_input,
+                        ),
+                    ];
+
+                    let expected = circuit_1.check_instance(&public_input).is_ok();
+                    assert_eq!(
+                        accepted, [expected; 3],
+                        "n = {}, input = {:?}",
+                        n, public_input
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn fibonacci_num_rows() {
+        let n = 10;
+        assert_eq!(
+            FibonacciCircuit::<Fp, fib_1::FibonacciChip<Fp>>::new(n).num_rows(),
+            9
+        );
+        assert_eq!(
+            FibonacciCircuit::<Fp, fib_2::FibonacciChip<Fp>>::new(n).num_rows(),
+            10
+        );
+        assert_eq!(
+            FibonacciCircuit::<Fp, fib_3::FibonacciChip<Fp>>::new(n).num_rows(),
+            12
+        );
+    }
+
+    #[test]
+    fn fibonacci_constrained() {
+        let n = 10;
+        let public_input = vec![vec![Fp::from(1), Fp::from(1), Fp::from(55)]];
+
+        let circuit_1 = FibonacciCircuit::<Fp, fib_1::FibonacciChip<Fp>>::new(n);
+        assert_constrained(circuit_1.k(), &circuit_1, public_input.clone(), &[]);
+
+        let circuit_2 = FibonacciCircuit::<Fp, fib_2::FibonacciChip<Fp>>::new(n);
+        assert_constrained(circuit_2.k(), &circuit_2, public_input.clone(), &[]);
+
+        let circuit_3 = FibonacciCircuit::<Fp, fib_3::FibonacciChip<Fp>>::new(n);
+        assert_constrained(circuit_3.k(), &circuit_3, public_input, &[]);
+    }
+}
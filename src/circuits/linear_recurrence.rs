@@ -0,0 +1,189 @@
+use std::marker::PhantomData;
+
+use crate::backend::{circuit::*, plonk::*, Field};
+use crate::chips::linear_recurrence::{LinearRecurrenceChip, LinearRecurrenceConfig};
+use crate::circuits::utils::{linear_recurrence, min_k};
+
+/// A recurrence `f(n) = c_1 * f(n - 1) + ... + c_ORDER * f(n - ORDER)` with
+/// constant coefficients. The initial terms are public inputs, so sequences
+/// sharing a recurrence (e.g. Fibonacci and Lucas) share a circuit.
+pub trait Recurrence<const ORDER: usize> {
+    const NAME: &'static str;
+    /// `[c_1, ..., c_ORDER]`
+    const COEFFICIENTS: [u64; ORDER];
+}
+
+/// f(n) = f(n - 1) + f(n - 2), also covers Lucas numbers
+pub struct Fibonacci;
+
+impl Recurrence<2> for Fibonacci {
+    const NAME: &'static str = "fibonacci";
+    const COEFFICIENTS: [u64; 2] = [1, 1];
+}
+
+/// f(n) = f(n - 1) + f(n - 2) + f(n - 3)
+pub struct Tribonacci;
+
+impl Recurrence<3> for Tribonacci {
+    const NAME: &'static str = "tribonacci";
+    const COEFFICIENTS: [u64; 3] = [1, 1, 1];
+}
+
+/// f(n) = 2 * f(n - 1) + f(n - 2)
+pub struct Pell;
+
+impl Recurrence<2> for Pell {
+    const NAME: &'static str = "pell";
+    const COEFFICIENTS: [u64; 2] = [2, 1];
+}
+
+/// Proves `f(n)` given `f(1), ..., f(ORDER)`, with the instance column laid out
+/// as `[f(1), ..., f(ORDER), f(n)]`.
+pub struct LinearRecurrenceCircuit<F, R, const ORDER: usize> {
+    // index of the term exposed as the public output
+    n: usize,
+    _marker: PhantomData<(F, R)>,
+}
+
+impl<F: Field + From<u64>, R: Recurrence<ORDER>, const ORDER: usize>
+    LinearRecurrenceCircuit<F, R, ORDER>
+{
+    pub fn new(n: usize) -> Self {
+        assert!(n > ORDER, "{} needs n > {}, got {}", R::NAME, ORDER, n);
+
+        Self {
+            n,
+            _marker: PhantomData,
+        }
+    }
+
+    // row r holds f(r + 1)
+    pub fn num_rows(&self) -> usize {
+        self.n
+    }
+
+    pub fn k(&self) -> u32 {
+        min_k::<F, Self>(self.num_rows())
+    }
+
+    pub fn check_instance(&self, instance: &[F]) -> Result<(), Error> {
+        if instance.len() != ORDER + 1 {
+            return Err(Error::InvalidInstances);
+        }
+
+        let out = linear_recurrence(&instance[..ORDER], &R::COEFFICIENTS, self.n);
+        if out != instance[ORDER] {
+            return Err(Error::InvalidInstances);
+        }
+
+        Ok(())
+    }
+}
+
+impl<F: Field + From<u64>, R: Recurrence<ORDER>, const ORDER: usize> Circuit<F>
+    for LinearRecurrenceCircuit<F, R, ORDER>
+{
+    type Config = LinearRecurrenceConfig<F, ORDER>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::new(self.n)
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = meta.advice_column();
+        let instance = meta.instance_column();
+
+        LinearRecurrenceChip::configure(meta, advice, instance, R::COEFFICIENTS.map(F::from))
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = LinearRecurrenceChip::construct(config);
+
+        let out_cell = chip.assign(layouter.namespace(|| "private out"), self.num_rows())?;
+
+        chip.expose_public(layouter.namespace(|| "out"), &out_cell, ORDER)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Fibonacci, LinearRecurrenceCircuit, Pell, Recurrence, Tribonacci};
+    use crate::backend::{dev::MockProver, Fp};
+    use crate::circuits::fib_3::FibonacciCircuit3;
+    use crate::circuits::under_constrained::assert_constrained;
+
+    fn check<R: Recurrence<ORDER>, const ORDER: usize>(n: usize, public_input: &[u64]) {
+        let circuit = LinearRecurrenceCircuit::<Fp, R, ORDER>::new(n);
+        let mut public_input: Vec<_> = public_input.iter().map(|&v| Fp::from(v)).collect();
+        circuit.check_instance(&public_input).unwrap();
+
+        let prover = MockProver::run(circuit.k(), &circuit, vec![public_input.clone()]).unwrap();
+        prover.assert_satisfied();
+
+        public_input[ORDER] += Fp::one();
+        assert!(circuit.check_instance(&public_input).is_err());
+        let prover = MockProver::run(circuit.k(), &circuit, vec![public_input]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn linear_recurrence() {
+        check::<Fibonacci, 2>(10, &[1, 1, 55]);
+        check::<Fibonacci, 2>(10, &[2, 1, 76]); // Lucas
+        check::<Tribonacci, 3>(10, &[1, 1, 2, 149]);
+        check::<Pell, 2>(10, &[0, 1, 985]);
+    }
+
+    // Fibonacci is the linear recurrence [1, 1], on the same layout as fib_3
+    #[test]
+    fn linear_recurrence_matches_fib_3() {
+        for n in [3, 10, 50] {
+            let fib = FibonacciCircuit3::<Fp>::new(n);
+            let recurrence = LinearRecurrenceCircuit::<Fp, Fibonacci, 2>::new(n);
+            assert_eq!(fib.k(), recurrence.k());
+
+            let out = crate::circuits::utils::fibonacci(Fp::from(1), Fp::from(1), n);
+            let public_input = vec![Fp::from(1), Fp::from(1), out];
+
+            for prover in [
+                MockProver::run(fib.k(), &fib, vec![public_input.clone()]).unwrap(),
+                MockProver::run(recurrence.k(), &recurrence, vec![public_input.clone()]).unwrap(),
+            ] {
+                prover.assert_satisfied();
+            }
+        }
+    }
+
+    #[test]
+    fn linear_recurrence_constrained() {
+        let circuit = LinearRecurrenceCircuit::<Fp, Tribonacci, 3>::new(10);
+        let public_input = [1, 1, 2, 149].map(Fp::from).to_vec();
+        assert_constrained(circuit.k(), &circuit, vec![public_input], &[]);
+    }
+
+    #[cfg(feature = "dev-graph")]
+    #[test]
+    fn plot_linear_recurrence() {
+        use plotters::prelude::*;
+        use std::path::Path;
+
+        let filename = Path::new("./devgraphs/linear-recurrence-layout.png");
+        let title = "Linear Recurrence Layout";
+
+        let root = BitMapBackend::new(filename, (1024, 3096)).into_drawing_area();
+        root.fill(&WHITE).unwrap();
+        let root = root.titled(title, ("sans-serif", 60)).unwrap();
+
+        let circuit = LinearRecurrenceCircuit::<Fp, Tribonacci, 3>::new(10);
+        crate::backend::dev::CircuitLayout::default()
+            .render(circuit.k(), &circuit, &root)
+            .unwrap();
+    }
+}
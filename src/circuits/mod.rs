@@ -1,8 +1,23 @@
+pub mod comparison;
 pub mod fib_1;
 pub mod fib_2;
 pub mod fib_3;
+pub mod fib_doubling;
+pub mod fib_public_index;
+pub mod fib_range_check;
+pub mod fib_wide;
+pub mod fibonacci;
+pub mod linear_recurrence;
 pub mod range_check_1;
 pub mod range_check_2;
 pub mod range_check_3;
 pub mod range_check_4;
-// pub mod utils;
+pub mod range_check_canonical;
+pub mod range_check_interval;
+pub mod range_check_signed;
+pub mod range_constrained;
+#[cfg(test)]
+pub(crate) mod strategies;
+#[cfg(test)]
+pub(crate) mod under_constrained;
+pub mod utils;
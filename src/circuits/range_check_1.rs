@@ -1,17 +1,20 @@
-use halo2_proofs_zcash::{
-    arithmetic::FieldExt,
+use crate::backend::{
     circuit::{Layouter, SimpleFloorPlanner, Value},
     plonk::{Assigned, Circuit, ConstraintSystem, Error},
+    Field,
 };
-
 use crate::chips::range_check_1::{RangeCheckChip, RangeCheckConfig};
 
 #[derive(Default)]
-struct RangeCheckCircuit1<F: FieldExt, const RANGE: usize> {
-    value: Value<Assigned<F>>,
+pub struct RangeCheckCircuit1<F: Field, const RANGE: usize> {
+    pub value: Value<Assigned<F>>,
+}
+
+impl<F: Field, const RANGE: usize> RangeCheckCircuit1<F, RANGE> {
+    pub const NAME: &str = "range_check_1";
 }
 
-impl<F: FieldExt, const RANGE: usize> Circuit<F> for RangeCheckCircuit1<F, RANGE> {
+impl<F: Field, const RANGE: usize> Circuit<F> for RangeCheckCircuit1<F, RANGE> {
     type Config = RangeCheckConfig;
     type FloorPlanner = SimpleFloorPlanner;
 
@@ -38,14 +41,17 @@ impl<F: FieldExt, const RANGE: usize> Circuit<F> for RangeCheckCircuit1<F, RANGE
 
 #[cfg(test)]
 mod tests {
-    use halo2_proofs_zcash::{
+    use proptest::prelude::*;
+
+    use crate::backend::{
+        any_advice,
         circuit::Value,
         dev::{FailureLocation, MockProver, VerifyFailure},
-        pasta::Fp,
-        plonk::Any,
+        Fp,
     };
-
     use crate::circuits::range_check_1::RangeCheckCircuit1;
+    use crate::circuits::strategies::{field, in_range, values};
+    use crate::circuits::under_constrained::assert_constrained;
 
     #[test]
     fn range_check_1() {
@@ -76,12 +82,53 @@ mod tests {
                     region: (0, "Assign value").into(),
                     offset: 0
                 },
-                cell_values: vec![(((Any::Advice, 0).into(), 0).into(), "0x16".to_string())]
+                cell_values: vec![(((any_advice(), 0).into(), 0).into(), "0x16".to_string())]
             }])
         );
     }
 
-    #[cfg(feature = "dev-graph-zcash")]
+    #[test]
+    fn range_check_1_constrained() {
+        let circuit = RangeCheckCircuit1::<Fp, 8> {
+            value: Value::known(Fp::from(5).into()),
+        };
+        assert_constrained(4, &circuit, vec![], &[]);
+    }
+
+    // RANGE is a const generic, so each tested RANGE is instantiated here
+    fn accepts(range: usize, value: i128) -> bool {
+        fn run<const RANGE: usize>(value: i128) -> bool {
+            let circuit = RangeCheckCircuit1::<Fp, RANGE> {
+                value: Value::known(field(value).into()),
+            };
+            MockProver::run(4, &circuit, vec![])
+                .unwrap()
+                .verify()
+                .is_ok()
+        }
+
+        match range {
+            2 => run::<2>(value),
+            8 => run::<8>(value),
+            16 => run::<16>(value),
+            _ => unreachable!(),
+        }
+    }
+
+    // RANGE, and a value to check against it
+    fn inputs() -> impl Strategy<Value = (usize, i128)> {
+        prop::sample::select(vec![2usize, 8, 16])
+            .prop_flat_map(|range| (Just(range), values(range as i128)))
+    }
+
+    proptest! {
+        #[test]
+        fn range_check_1_property((range, value) in inputs()) {
+            prop_assert_eq!(accepts(range, value), in_range(value, range as i128));
+        }
+    }
+
+    #[cfg(feature = "dev-graph")]
     #[test]
     fn plot_range_check_1() {
         use std::path::Path;
@@ -99,7 +146,7 @@ mod tests {
         let circuit = RangeCheckCircuit1::<Fp, RANGE> {
             value: Value::unknown(),
         };
-        halo2_proofs_zcash::dev::CircuitLayout::default()
+        crate::backend::dev::CircuitLayout::default()
             .render(4, &circuit, &root)
             .unwrap();
     }
@@ -1,21 +1,30 @@
-use halo2_proofs_zcash::{
-    arithmetic::FieldExt,
+use crate::backend::{
     circuit::{Layouter, SimpleFloorPlanner, Value},
     plonk::{Assigned, Circuit, ConstraintSystem, Error},
+    Field,
 };
-
 use crate::chips::range_check_2::{RangeCheckChip, RangeCheckConfig};
+use crate::chips::table_registry::TableRegistry;
 
 #[derive(Default)]
-struct RangeCheckCircuit2<F: FieldExt, const RANGE: usize, const LOOKUP_TABLE_RANGE: usize> {
-    value: Value<Assigned<F>>,
-    lookup_value: Value<Assigned<F>>,
+pub struct RangeCheckCircuit2<F: Field, const RANGE: usize, const LOOKUP_TABLE_RANGE: usize> {
+    pub value: Value<Assigned<F>>,
+    pub lookup_value: Value<Assigned<F>>,
+}
+
+impl<F: Field, const RANGE: usize, const LOOKUP_TABLE_RANGE: usize>
+    RangeCheckCircuit2<F, RANGE, LOOKUP_TABLE_RANGE>
+{
+    pub const NAME: &str = "range_check_2";
 }
 
-impl<F: FieldExt, const RANGE: usize, const LOOKUP_TABLE_RANGE: usize> Circuit<F>
+impl<F: Field, const RANGE: usize, const LOOKUP_TABLE_RANGE: usize> Circuit<F>
     for RangeCheckCircuit2<F, RANGE, LOOKUP_TABLE_RANGE>
 {
-    type Config = RangeCheckConfig<F, RANGE, LOOKUP_TABLE_RANGE>;
+    type Config = (
+        RangeCheckConfig<F, RANGE, LOOKUP_TABLE_RANGE>,
+        TableRegistry,
+    );
     type FloorPlanner = SimpleFloorPlanner;
 
     fn without_witnesses(&self) -> Self {
@@ -24,37 +33,47 @@ impl<F: FieldExt, const RANGE: usize, const LOOKUP_TABLE_RANGE: usize> Circuit<F
 
     fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
         let advice = meta.advice_column();
+        let mut tables = TableRegistry::new();
 
-        RangeCheckChip::<F, RANGE, LOOKUP_TABLE_RANGE>::configure(meta, advice)
+        let config =
+            RangeCheckChip::<F, RANGE, LOOKUP_TABLE_RANGE>::configure(meta, advice, &mut tables);
+        (config, tables)
     }
 
     fn synthesize(
         &self,
-        config: Self::Config,
+        (config, tables): Self::Config,
         mut layouter: impl Layouter<F>,
     ) -> Result<(), Error> {
-        config.table.load(&mut layouter)?;
+        tables.load(&mut layouter)?;
 
         let chip = RangeCheckChip::<F, RANGE, LOOKUP_TABLE_RANGE>::construct(config);
 
         chip.assign_simple(layouter.namespace(|| "value"), self.value)?;
 
         chip.assign_lookup_table(layouter.namespace(|| "value"), self.lookup_value)?;
-        Ok(())
+        tables.finish()
     }
 }
 
 #[cfg(test)]
 mod tests {
 
-    use halo2_proofs_zcash::{
-        circuit::Value,
+    use proptest::prelude::*;
+
+    use crate::backend::{
+        any_advice,
+        circuit::{Layouter, SimpleFloorPlanner, Value},
         dev::{FailureLocation, MockProver, VerifyFailure},
-        pasta::Fp,
-        plonk::Any,
+        is_lookup_failure,
+        plonk::{Advice, Assigned, Circuit, Column, ConstraintSystem, Error},
+        Fp,
     };
-
+    use crate::chips::range_check_2::{RangeCheckChip, RangeCheckConfig};
+    use crate::chips::table_registry::TableRegistry;
     use crate::circuits::range_check_2::RangeCheckCircuit2;
+    use crate::circuits::strategies::{field, in_range, values};
+    use crate::circuits::under_constrained::assert_constrained;
 
     #[test]
     fn range_check_2() {
@@ -82,30 +101,202 @@ mod tests {
                 lookup_value: Value::known(Fp::from(LOOKUP_RANGE as u64).into()),
             };
             let prover = MockProver::run(k, &circuit, vec![]).unwrap();
+            let failures = prover.verify().unwrap_err();
+            assert_eq!(failures.len(), 2);
             assert_eq!(
-                prover.verify(),
-                Err(vec![
-                    VerifyFailure::ConstraintNotSatisfied {
-                        constraint: ((0, "Range Check").into(), 0, "range check").into(),
-                        location: FailureLocation::InRegion {
-                            region: (1, "Assign simple").into(),
-                            offset: 0
-                        },
-                        cell_values: vec![(((Any::Advice, 0).into(), 0).into(), "0x8".to_string())]
+                failures[0],
+                VerifyFailure::ConstraintNotSatisfied {
+                    constraint: ((0, "Range Check").into(), 0, "range check").into(),
+                    location: FailureLocation::InRegion {
+                        region: (1, "Assign simple").into(),
+                        offset: 0
                     },
-                    VerifyFailure::Lookup {
-                        lookup_index: 0,
-                        location: FailureLocation::InRegion {
-                            region: (2, "Assign lookup table").into(),
-                            offset: 0
-                        }
-                    }
-                ])
+                    cell_values: vec![(((any_advice(), 0).into(), 0).into(), "0x8".to_string())]
+                }
+            );
+            assert!(is_lookup_failure(
+                &failures[1],
+                &FailureLocation::InRegion {
+                    region: (2, "Assign lookup table").into(),
+                    offset: 0
+                }
+            ));
+        }
+    }
+
+    const BATCH_RANGE: usize = 8; // 3-bit value
+    const BATCH_LOOKUP_RANGE: usize = 256; // 8-bit value
+
+    // Range-check each batch in a single region. The lookup values are checked
+    // twice, once as new witnesses and once copied from another region.
+    struct BatchCircuit {
+        values: Vec<Value<Assigned<Fp>>>,
+        lookup_values: Vec<Value<Assigned<Fp>>>,
+    }
+
+    impl Circuit<Fp> for BatchCircuit {
+        type Config = (
+            RangeCheckConfig<Fp, BATCH_RANGE, BATCH_LOOKUP_RANGE>,
+            Column<Advice>,
+            TableRegistry,
+        );
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                values: vec![Value::unknown(); self.values.len()],
+                lookup_values: vec![Value::unknown(); self.lookup_values.len()],
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            let advice = meta.advice_column();
+            let witness = meta.advice_column();
+            meta.enable_equality(advice);
+            meta.enable_equality(witness);
+
+            let mut tables = TableRegistry::new();
+
+            (
+                RangeCheckChip::configure(meta, advice, &mut tables),
+                witness,
+                tables,
+            )
+        }
+
+        fn synthesize(
+            &self,
+            (config, witness, tables): Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            tables.load(&mut layouter)?;
+
+            let cells = layouter.assign_region(
+                || "Witness values",
+                |mut region| {
+                    self.lookup_values
+                        .iter()
+                        .enumerate()
+                        .map(|(offset, value)| {
+                            region.assign_advice(|| "value", witness, offset, || *value)
+                        })
+                        .collect::<Result<Vec<_>, _>>()
+                },
+            )?;
+
+            let chip = RangeCheckChip::construct(config);
+            chip.assign_simple_batch(layouter.namespace(|| "values"), &self.values)?;
+            chip.assign_lookup_table_batch(
+                layouter.namespace(|| "lookup values"),
+                &self.lookup_values,
+            )?;
+            chip.copy_lookup_table_batch(layouter.namespace(|| "copied values"), &cells)?;
+
+            tables.finish()
+        }
+    }
+
+    fn known(values: &[u64]) -> Vec<Value<Assigned<Fp>>> {
+        values
+            .iter()
+            .map(|v| Value::known(Fp::from(*v).into()))
+            .collect()
+    }
+
+    #[test]
+    fn range_check_2_batch() {
+        let k = 9;
+
+        let values: Vec<u64> = (0..20).map(|i| i % BATCH_RANGE as u64).collect();
+        let lookup_values: Vec<u64> = (0..100)
+            .map(|i| (i * 37) % BATCH_LOOKUP_RANGE as u64)
+            .collect();
+
+        let circuit = BatchCircuit {
+            values: known(&values),
+            lookup_values: known(&lookup_values),
+        };
+        let prover = MockProver::run(k, &circuit, vec![]).unwrap();
+        prover.assert_satisfied();
+
+        // A single out-of-range value fails both lookups, on its own row
+        let mut out_of_range = lookup_values;
+        out_of_range[37] = BATCH_LOOKUP_RANGE as u64;
+
+        let circuit = BatchCircuit {
+            values: known(&values),
+            lookup_values: known(&out_of_range),
+        };
+        let prover = MockProver::run(k, &circuit, vec![]).unwrap();
+        let failures = prover.verify().unwrap_err();
+        assert_eq!(failures.len(), 2);
+        for failure in failures {
+            match failure {
+                VerifyFailure::Lookup {
+                    location: FailureLocation::InRegion { offset, .. },
+                    ..
+                } => assert_eq!(offset, 37),
+                _ => panic!("unexpected failure {:?}", failure),
+            }
+        }
+    }
+
+    #[test]
+    fn range_check_2_constrained() {
+        let circuit = RangeCheckCircuit2::<Fp, 8, 256> {
+            value: Value::known(Fp::from(5).into()),
+            lookup_value: Value::known(Fp::from(200).into()),
+        };
+        assert_constrained(9, &circuit, vec![], &[]);
+    }
+
+    // RANGE and LOOKUP_RANGE are const generics, so each tested pair is
+    // instantiated here
+    fn accepts(ranges: (usize, usize), value: i128, lookup_value: i128) -> bool {
+        fn run<const RANGE: usize, const LOOKUP_RANGE: usize>(
+            value: i128,
+            lookup_value: i128,
+        ) -> bool {
+            let circuit = RangeCheckCircuit2::<Fp, RANGE, LOOKUP_RANGE> {
+                value: Value::known(field(value).into()),
+                lookup_value: Value::known(field(lookup_value).into()),
+            };
+            MockProver::run(9, &circuit, vec![])
+                .unwrap()
+                .verify()
+                .is_ok()
+        }
+
+        match ranges {
+            (4, 16) => run::<4, 16>(value, lookup_value),
+            (8, 256) => run::<8, 256>(value, lookup_value),
+            _ => unreachable!(),
+        }
+    }
+
+    // (RANGE, LOOKUP_RANGE), and a value to check against each
+    fn inputs() -> impl Strategy<Value = ((usize, usize), i128, i128)> {
+        prop::sample::select(vec![(4usize, 16usize), (8, 256)]).prop_flat_map(|ranges| {
+            (
+                Just(ranges),
+                values(ranges.0 as i128),
+                values(ranges.1 as i128),
+            )
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn range_check_2_property((ranges, value, lookup_value) in inputs()) {
+            let (range, lookup_range) = (ranges.0 as i128, ranges.1 as i128);
+            prop_assert_eq!(
+                accepts(ranges, value, lookup_value),
+                in_range(value, range) && in_range(lookup_value, lookup_range)
             );
         }
     }
 
-    #[cfg(feature = "dev-graph-zcash")]
+    #[cfg(feature = "dev-graph")]
     #[test]
     fn plot_range_check_2() {
         use std::path::Path;
@@ -125,7 +316,7 @@ mod tests {
             value: Value::unknown(),
             lookup_value: Value::unknown(),
         };
-        halo2_proofs_zcash::dev::CircuitLayout::default()
+        crate::backend::dev::CircuitLayout::default()
             .render(4, &circuit, &root)
             .unwrap();
     }
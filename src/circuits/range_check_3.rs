@@ -1,21 +1,30 @@
-use halo2_proofs_zcash::{
-    arithmetic::FieldExt,
+use crate::backend::{
     circuit::{floor_planner::V1, Layouter, Value},
     plonk::{Assigned, Circuit, ConstraintSystem, Error},
+    Field,
 };
-
 use crate::chips::range_check_3::{RangeCheckChip, RangeCheckConfig};
+use crate::chips::table_registry::TableRegistry;
 
 #[derive(Default)]
-struct RangeCheckCircuit3<F: FieldExt, const LOOKUP_TABLE_RANGE: usize, const NUM_BITS: usize> {
-    num_bits: Value<u8>,
-    value: Value<Assigned<F>>,
+pub struct RangeCheckCircuit3<F: Field, const LOOKUP_TABLE_RANGE: usize, const NUM_BITS: usize> {
+    pub num_bits: Value<u8>,
+    pub value: Value<Assigned<F>>,
+}
+
+impl<F: Field, const LOOKUP_TABLE_RANGE: usize, const NUM_BITS: usize>
+    RangeCheckCircuit3<F, LOOKUP_TABLE_RANGE, NUM_BITS>
+{
+    pub const NAME: &str = "range_check_3";
 }
 
-impl<F: FieldExt, const LOOKUP_TABLE_RANGE: usize, const NUM_BITS: usize> Circuit<F>
+impl<F: Field, const LOOKUP_TABLE_RANGE: usize, const NUM_BITS: usize> Circuit<F>
     for RangeCheckCircuit3<F, LOOKUP_TABLE_RANGE, NUM_BITS>
 {
-    type Config = RangeCheckConfig<F, LOOKUP_TABLE_RANGE, NUM_BITS>;
+    type Config = (
+        RangeCheckConfig<F, LOOKUP_TABLE_RANGE, NUM_BITS>,
+        TableRegistry,
+    );
     type FloorPlanner = V1;
 
     fn without_witnesses(&self) -> Self {
@@ -25,34 +34,48 @@ impl<F: FieldExt, const LOOKUP_TABLE_RANGE: usize, const NUM_BITS: usize> Circui
     fn configure(cs: &mut ConstraintSystem<F>) -> Self::Config {
         let num_bits_col = cs.advice_column();
         let value_col = cs.advice_column();
+        let mut tables = TableRegistry::new();
 
-        RangeCheckChip::<F, LOOKUP_TABLE_RANGE, NUM_BITS>::configure(cs, value_col, num_bits_col)
+        let config = RangeCheckChip::<F, LOOKUP_TABLE_RANGE, NUM_BITS>::configure(
+            cs,
+            value_col,
+            num_bits_col,
+            &mut tables,
+        );
+        (config, tables)
     }
 
     fn synthesize(
         &self,
-        config: Self::Config,
+        (config, tables): Self::Config,
         mut layouter: impl Layouter<F>,
     ) -> Result<(), Error> {
-        config.table.load(&mut layouter)?;
+        tables.load(&mut layouter)?;
 
         let chip = RangeCheckChip::<F, LOOKUP_TABLE_RANGE, NUM_BITS>::construct(config);
         chip.assign_lookup_table(layouter.namespace(|| "value"), self.value, self.num_bits)?;
 
-        Ok(())
+        tables.finish()
     }
 }
 
 #[cfg(test)]
 mod tests {
 
-    use halo2_proofs_zcash::{
-        circuit::Value,
+    use proptest::prelude::*;
+
+    use crate::backend::{
+        circuit::{floor_planner::V1, Layouter, Value},
         dev::{FailureLocation, MockProver, VerifyFailure},
-        pasta::Fp,
+        is_lookup_failure,
+        plonk::{Advice, Assigned, Circuit, Column, ConstraintSystem, Error, Instance},
+        Fp,
     };
-
+    use crate::chips::range_check_3::{PublicNumBits, RangeCheckChip, RangeCheckConfig};
+    use crate::chips::table_registry::TableRegistry;
     use crate::circuits::range_check_3::RangeCheckCircuit3;
+    use crate::circuits::strategies::{field, in_range, values};
+    use crate::circuits::under_constrained::assert_constrained;
 
     #[test]
     fn range_check_3() {
@@ -79,19 +102,295 @@ mod tests {
             value: Value::known(Fp::from(NUM_BITS as u64).into()),
         };
         let prover = MockProver::run(k, &circuit, vec![]).unwrap();
-        assert_eq!(
-            prover.verify(),
-            Err(vec![VerifyFailure::Lookup {
-                lookup_index: 0,
-                location: FailureLocation::InRegion {
-                    region: (1, "Assign lookup table").into(),
-                    offset: 0
-                }
-            }])
+        let failures = prover.verify().unwrap_err();
+        assert_eq!(failures.len(), 1);
+        assert!(is_lookup_failure(
+            &failures[0],
+            &FailureLocation::InRegion {
+                region: (1, "Assign lookup table").into(),
+                offset: 0
+            }
+        ));
+    }
+
+    const BATCH_LOOKUP_RANGE: usize = 256; // 8-bit value
+    const BATCH_NUM_BITS: usize = 8; // 8-bit value
+
+    // Range-check `(value, num_bits)` pairs in a single region, as new witnesses
+    // and copied from another region
+    struct BatchCircuit {
+        values: Vec<(Value<Assigned<Fp>>, Value<u8>)>,
+    }
+
+    impl Circuit<Fp> for BatchCircuit {
+        type Config = (
+            RangeCheckConfig<Fp, BATCH_LOOKUP_RANGE, BATCH_NUM_BITS>,
+            Column<Advice>,
+            TableRegistry,
         );
+        type FloorPlanner = V1;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                values: vec![(Value::unknown(), Value::unknown()); self.values.len()],
+            }
+        }
+
+        fn configure(cs: &mut ConstraintSystem<Fp>) -> Self::Config {
+            let num_bits_col = cs.advice_column();
+            let value_col = cs.advice_column();
+            let witness = cs.advice_column();
+            cs.enable_equality(value_col);
+            cs.enable_equality(witness);
+            let mut tables = TableRegistry::new();
+
+            (
+                RangeCheckChip::configure(cs, num_bits_col, value_col, &mut tables),
+                witness,
+                tables,
+            )
+        }
+
+        fn synthesize(
+            &self,
+            (config, witness, tables): Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            tables.load(&mut layouter)?;
+
+            let cells = layouter.assign_region(
+                || "Witness values",
+                |mut region| {
+                    self.values
+                        .iter()
+                        .enumerate()
+                        .map(|(offset, (value, num_bits))| {
+                            region
+                                .assign_advice(|| "value", witness, offset, || *value)
+                                .map(|cell| (cell, *num_bits))
+                        })
+                        .collect::<Result<Vec<_>, _>>()
+                },
+            )?;
+
+            let chip = RangeCheckChip::construct(config);
+            chip.assign_lookup_table_batch(layouter.namespace(|| "values"), &self.values)?;
+            chip.copy_lookup_table_batch(layouter.namespace(|| "copied values"), &cells)?;
+
+            tables.finish()
+        }
+    }
+
+    fn known(values: &[(u64, u8)]) -> Vec<(Value<Assigned<Fp>>, Value<u8>)> {
+        values
+            .iter()
+            .map(|(v, num_bits)| (Value::known(Fp::from(*v).into()), Value::known(*num_bits)))
+            .collect()
+    }
+
+    #[test]
+    fn range_check_3_batch() {
+        let k = 9;
+
+        let values: Vec<(u64, u8)> = (1..=200u64)
+            .map(|v| (v, (64 - v.leading_zeros()) as u8))
+            .collect();
+
+        let circuit = BatchCircuit {
+            values: known(&values),
+        };
+        let prover = MockProver::run(k, &circuit, vec![]).unwrap();
+        prover.assert_satisfied();
+
+        // A single value with the wrong number of bits fails both lookups, on
+        // its own row
+        let mut wrong = values;
+        wrong[37].1 += 1;
+
+        let circuit = BatchCircuit {
+            values: known(&wrong),
+        };
+        let prover = MockProver::run(k, &circuit, vec![]).unwrap();
+        let failures = prover.verify().unwrap_err();
+        assert_eq!(failures.len(), 2);
+        for failure in failures {
+            match failure {
+                VerifyFailure::Lookup {
+                    location: FailureLocation::InRegion { offset, .. },
+                    ..
+                } => assert_eq!(offset, 37),
+                _ => panic!("unexpected failure {:?}", failure),
+            }
+        }
+    }
+
+    // Range-check `value` to exactly n bits, or to its `tag` and at most n bits,
+    // with n a `constant` or the first instance cell
+    struct PublicNumBitsCircuit {
+        value: Value<u64>,
+        tag: Option<Value<u8>>,
+        constant: Option<u8>,
+    }
+
+    impl Circuit<Fp> for PublicNumBitsCircuit {
+        type Config = (
+            RangeCheckConfig<Fp, BATCH_LOOKUP_RANGE, BATCH_NUM_BITS>,
+            Column<Instance>,
+            TableRegistry,
+        );
+        type FloorPlanner = V1;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                value: Value::unknown(),
+                tag: self.tag.map(|_| Value::unknown()),
+                constant: self.constant,
+            }
+        }
+
+        fn configure(cs: &mut ConstraintSystem<Fp>) -> Self::Config {
+            let constants = cs.fixed_column();
+            cs.enable_constant(constants);
+
+            let num_bits_col = cs.advice_column();
+            let value_col = cs.advice_column();
+            let instance = cs.instance_column();
+            cs.enable_equality(instance);
+            let mut tables = TableRegistry::new();
+
+            (
+                RangeCheckChip::configure(cs, num_bits_col, value_col, &mut tables),
+                instance,
+                tables,
+            )
+        }
+
+        fn synthesize(
+            &self,
+            (config, instance, tables): Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            tables.load(&mut layouter)?;
+
+            let n = match self.constant {
+                Some(n) => PublicNumBits::Constant(n),
+                None => PublicNumBits::Instance {
+                    column: instance,
+                    row: 0,
+                },
+            };
+            let value = self.value.map(|v| Fp::from(v).into());
+
+            let chip = RangeCheckChip::construct(config);
+            let layouter = layouter.namespace(|| "value");
+            match self.tag {
+                None => chip.assign_exact(layouter, value, n)?,
+                Some(tag) => chip.assign_at_most(layouter, value, tag, n)?,
+            };
+
+            tables.finish()
+        }
+    }
+
+    #[test]
+    fn range_check_3_public_num_bits() {
+        let k = 9;
+
+        let verify = |value, tag: Option<u8>, n: u8, constant: bool| {
+            let circuit = PublicNumBitsCircuit {
+                value: Value::known(value),
+                tag: tag.map(Value::known),
+                constant: constant.then_some(n),
+            };
+            MockProver::run(k, &circuit, vec![vec![Fp::from(n as u64)]])
+                .unwrap()
+                .verify()
+                .is_ok()
+        };
+
+        for constant in [true, false] {
+            // 5 = 0b101 has exactly 3 bits
+            assert!(verify(5, None, 3, constant));
+            assert!(!verify(5, None, 2, constant));
+            assert!(!verify(5, None, 4, constant));
+            assert!(verify(0, None, 1, constant));
+
+            // and fits in 3 bits or more
+            for n in 3..=8 {
+                assert!(verify(5, Some(3), n, constant));
+            }
+            assert!(!verify(5, Some(3), 2, constant));
+            // a tag below the bit length of the value fails the lookup
+            assert!(!verify(5, Some(2), 2, constant));
+        }
+    }
+
+    #[test]
+    fn range_check_3_constrained() {
+        let circuit = RangeCheckCircuit3::<Fp, 256, 8> {
+            num_bits: Value::known(6),
+            value: Value::known(Fp::from(37).into()),
+        };
+        assert_constrained(9, &circuit, vec![], &[]);
+    }
+
+    // NUM_BITS is a const generic, so each tested NUM_BITS is instantiated here
+    fn accepts(max_bits: usize, num_bits: u8, value: i128) -> bool {
+        fn run<const LOOKUP_RANGE: usize, const NUM_BITS: usize>(
+            num_bits: u8,
+            value: i128,
+        ) -> bool {
+            let circuit = RangeCheckCircuit3::<Fp, LOOKUP_RANGE, NUM_BITS> {
+                num_bits: Value::known(num_bits),
+                value: Value::known(field(value).into()),
+            };
+            MockProver::run(9, &circuit, vec![])
+                .unwrap()
+                .verify()
+                .is_ok()
+        }
+
+        match max_bits {
+            4 => run::<16, 4>(num_bits, value),
+            8 => run::<256, 8>(num_bits, value),
+            _ => unreachable!(),
+        }
+    }
+
+    // The tag of `value` in the table, 0 being tagged 1
+    fn tag(value: i128) -> u8 {
+        (128 - value.leading_zeros()).max(1) as u8
+    }
+
+    // Values with their tag, or with an arbitrary one
+    fn tagged_values(max_bits: usize) -> impl Strategy<Value = (u8, i128)> {
+        let max = max_bits as u8;
+        prop_oneof![
+            (1..=max).prop_flat_map(|n| {
+                let lo = if n == 1 { 0 } else { 1i128 << (n - 1) };
+                (Just(n), lo..1i128 << n)
+            }),
+            (0..=max + 2, values(1 << max_bits)),
+        ]
+    }
+
+    // NUM_BITS, and a tag and value to look up
+    fn inputs() -> impl Strategy<Value = (usize, (u8, i128))> {
+        prop::sample::select(vec![4usize, 8])
+            .prop_flat_map(|max_bits| (Just(max_bits), tagged_values(max_bits)))
+    }
+
+    proptest! {
+        #[test]
+        fn range_check_3_property((max_bits, (num_bits, value)) in inputs()) {
+            prop_assert_eq!(
+                accepts(max_bits, num_bits, value),
+                in_range(value, 1 << max_bits) && num_bits == tag(value)
+            );
+        }
     }
 
-    #[cfg(feature = "dev-graph-zcash")]
+    #[cfg(feature = "dev-graph")]
     #[test]
     fn plot_range_check_3() {
         use plotters::{backend::BitMapBackend, drawing::IntoDrawingArea, style::WHITE};
@@ -112,7 +411,7 @@ mod tests {
             num_bits: Value::unknown(),
             value: Value::unknown(),
         };
-        halo2_proofs_zcash::dev::CircuitLayout::default()
+        crate::backend::dev::CircuitLayout::default()
             .render(K, &circuit, &root)
             .unwrap();
     }
@@ -1,23 +1,28 @@
-use ff::PrimeFieldBits;
-use halo2_proofs_zcash::{
-    arithmetic::FieldExt,
+use crate::backend::{
     circuit::{floor_planner::V1, Layouter, Value},
     plonk::{Assigned, Circuit, ConstraintSystem, Error},
+    Field,
 };
-
 use crate::chips::range_check_4::RangeCheckDecomposeConfig;
+use crate::chips::table_registry::TableRegistry;
 
 #[derive(Clone)]
 
-struct RangeCheckDecomposeCircuit<F: FieldExt, const NUM_BITS: usize, const RANGE: usize> {
-    value: Value<Assigned<F>>,
-    num_bits: usize,
+pub struct RangeCheckDecomposeCircuit<F: Field, const NUM_BITS: usize, const RANGE: usize> {
+    pub value: Value<Assigned<F>>,
+    pub num_bits: usize,
+}
+
+impl<F: Field, const NUM_BITS: usize, const RANGE: usize>
+    RangeCheckDecomposeCircuit<F, NUM_BITS, RANGE>
+{
+    pub const NAME: &str = "range_check_4";
 }
 
-impl<F: FieldExt + PrimeFieldBits, const NUM_BITS: usize, const RANGE: usize> Circuit<F>
+impl<F: Field, const NUM_BITS: usize, const RANGE: usize> Circuit<F>
     for RangeCheckDecomposeCircuit<F, NUM_BITS, RANGE>
 {
-    type Config = RangeCheckDecomposeConfig<F, NUM_BITS, RANGE>;
+    type Config = (RangeCheckDecomposeConfig<F, NUM_BITS, RANGE>, TableRegistry);
     type FloorPlanner = V1;
 
     fn without_witnesses(&self) -> Self {
@@ -33,15 +38,19 @@ impl<F: FieldExt + PrimeFieldBits, const NUM_BITS: usize, const RANGE: usize> Ci
         meta.enable_constant(constants);
 
         let value = meta.advice_column();
-        RangeCheckDecomposeConfig::configure(meta, value)
+        let chunk = meta.advice_column();
+        let mut tables = TableRegistry::new();
+
+        let config = RangeCheckDecomposeConfig::configure(meta, value, chunk, &mut tables);
+        (config, tables)
     }
 
     fn synthesize(
         &self,
-        config: Self::Config,
+        (config, tables): Self::Config,
         mut layouter: impl Layouter<F>,
     ) -> Result<(), Error> {
-        config.table.load(&mut layouter)?;
+        tables.load(&mut layouter)?;
 
         // Witness the value somewhere
         let value = layouter.assign_region(
@@ -57,16 +66,29 @@ impl<F: FieldExt + PrimeFieldBits, const NUM_BITS: usize, const RANGE: usize> Ci
             self.num_bits,
         )?;
 
-        Ok(())
+        tables.finish()
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use halo2_proofs_zcash::{circuit::Value, dev::MockProver, pasta::Fp, plonk::Assigned};
     use rand;
 
+    use proptest::prelude::*;
+
+    use crate::backend::{
+        circuit::{floor_planner::V1, Layouter, Value},
+        dev::MockProver,
+        ff::{Field as _, PrimeField},
+        plonk::{Assigned, Circuit, ConstraintSystem, Error},
+        Fp,
+    };
+    use crate::chips::range_check_4::{RangeCheckDecomposeConfig, ShortRangeCheck};
+    use crate::chips::table_registry::{RangeTable, TableRegistry};
+    use crate::chips::{range_check_2, range_check_3};
     use crate::circuits::range_check_4::RangeCheckDecomposeCircuit;
+    use crate::circuits::strategies::{field, in_range, values};
+    use crate::circuits::under_constrained::assert_constrained;
 
     #[test]
     fn test_decompose_4() {
@@ -87,6 +109,555 @@ mod tests {
         prover.assert_satisfied();
     }
 
+    // Decompose a value and pin its chunks and remainder to `expected`
+    struct DecompositionCircuit {
+        value: Value<u64>,
+        num_bits: usize,
+        lowest_bits: bool,
+        short: ShortRangeCheck,
+        expected_chunks: Vec<u64>,
+        expected_remainder: u64,
+    }
+
+    impl Circuit<Fp> for DecompositionCircuit {
+        type Config = (RangeCheckDecomposeConfig<Fp, 10, 1024>, TableRegistry);
+        type FloorPlanner = V1;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                value: Value::unknown(),
+                num_bits: self.num_bits,
+                lowest_bits: self.lowest_bits,
+                short: self.short,
+                expected_chunks: self.expected_chunks.clone(),
+                expected_remainder: self.expected_remainder,
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            RangeCheckDecomposeCircuit::<Fp, 10, 1024>::configure(meta)
+        }
+
+        fn synthesize(
+            &self,
+            (config, tables): Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            tables.load(&mut layouter)?;
+
+            let value = layouter.assign_region(
+                || "Witness value",
+                |mut region| {
+                    region.assign_advice(
+                        || "Witness value",
+                        config.running_sum,
+                        0,
+                        || self.value.map(|v| Assigned::from(Fp::from(v))),
+                    )
+                },
+            )?;
+
+            let decomposition = if self.lowest_bits {
+                config.assign_lowest_bits(
+                    layouter.namespace(|| "Decompose"),
+                    value,
+                    self.num_bits,
+                )?
+            } else {
+                config.assign_with(
+                    layouter.namespace(|| "Decompose"),
+                    value,
+                    self.num_bits,
+                    self.short,
+                )?
+            };
+            assert_eq!(decomposition.chunks.len(), self.expected_chunks.len());
+            assert_eq!(
+                decomposition.running_sum.len(),
+                self.expected_chunks.len() + 1
+            );
+
+            layouter.assign_region(
+                || "Check decomposition",
+                |mut region| {
+                    for (chunk, expected) in decomposition.chunks.iter().zip(&self.expected_chunks)
+                    {
+                        region.constrain_constant(chunk.cell(), Fp::from(*expected))?;
+                    }
+                    region.constrain_constant(
+                        decomposition.remainder().cell(),
+                        Fp::from(self.expected_remainder),
+                    )
+                },
+            )?;
+
+            tables.finish()
+        }
+    }
+
+    #[test]
+    fn test_decomposition_chunks() {
+        let k = 11;
+        let value = 5 + (7 << 10) + (9 << 20) + (3 << 30);
+
+        let check = |num_bits, lowest_bits, expected_chunks: &[u64], expected_remainder| {
+            let circuit = DecompositionCircuit {
+                value: Value::known(value),
+                num_bits,
+                lowest_bits,
+                short: ShortRangeCheck::Shifted,
+                expected_chunks: expected_chunks.to_vec(),
+                expected_remainder,
+            };
+            MockProver::run(k, &circuit, vec![]).unwrap().verify()
+        };
+
+        // full decomposition
+        assert!(check(40, false, &[5, 7, 9, 3], 0).is_ok());
+        assert!(check(40, false, &[5, 7, 9, 4], 0).is_err());
+        // the last chunk is 2 bits wide
+        assert!(check(32, false, &[5, 7, 9, 3], 0).is_ok());
+        // the lowest 20 bits, leaving value >> 20 in z_C
+        assert!(check(20, true, &[5, 7], 9 + (3 << 10)).is_ok());
+        assert!(check(20, true, &[5, 7], 0).is_err());
+        // but the value does not fit in 20 bits
+        assert!(check(20, false, &[5, 7], 9 + (3 << 10)).is_err());
+    }
+
+    // The shifted and the tagged checks of the final partial chunk accept the
+    // same decompositions, the tagged one on fewer rows
+    #[test]
+    fn test_short_range_check_modes() {
+        let k = 11;
+        let value: u64 = 5 + (7 << 10) + (9 << 20) + (3 << 30);
+
+        for num_bits in [3, 13, 32, 35, 64] {
+            let windows = (num_bits + 9) / 10;
+            let chunks: Vec<u64> = (0..windows).map(|i| (value >> (10 * i)) & 1023).collect();
+            let fits = num_bits >= 64 || value >> num_bits == 0;
+
+            // the honest chunks, and a final chunk off by one
+            let mut tampered = chunks.clone();
+            *tampered.last_mut().unwrap() += 1;
+
+            for (expected_chunks, honest) in [(chunks, true), (tampered, false)] {
+                let verify = |short| {
+                    let circuit = DecompositionCircuit {
+                        value: Value::known(value),
+                        num_bits,
+                        lowest_bits: false,
+                        short,
+                        expected_chunks: expected_chunks.clone(),
+                        expected_remainder: 0,
+                    };
+                    MockProver::run(k, &circuit, vec![])
+                        .unwrap()
+                        .verify()
+                        .is_ok()
+                };
+
+                let shifted = verify(ShortRangeCheck::Shifted);
+                let tagged = verify(ShortRangeCheck::Tagged);
+                assert_eq!(shifted, honest && fits, "num_bits = {}", num_bits);
+                assert_eq!(shifted, tagged, "num_bits = {}", num_bits);
+            }
+
+            type Config = RangeCheckDecomposeConfig<Fp, 10, 1024>;
+            let shifted_rows = Config::num_rows(num_bits, ShortRangeCheck::Shifted);
+            let tagged_rows = Config::num_rows(num_bits, ShortRangeCheck::Tagged);
+            let saved = if num_bits % 10 > 0 { 2 } else { 0 };
+            assert_eq!(shifted_rows - tagged_rows, saved);
+        }
+    }
+
+    // Decompose all `values` in a single region, either witnessing them or
+    // copying them from another region
+    struct BatchCircuit {
+        values: Vec<Value<Assigned<Fp>>>,
+        num_bits: usize,
+        short: ShortRangeCheck,
+        copy: bool,
+    }
+
+    impl Circuit<Fp> for BatchCircuit {
+        type Config = (RangeCheckDecomposeConfig<Fp, 10, 1024>, TableRegistry);
+        type FloorPlanner = V1;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                values: vec![Value::unknown(); self.values.len()],
+                num_bits: self.num_bits,
+                short: self.short,
+                copy: self.copy,
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            RangeCheckDecomposeCircuit::<Fp, 10, 1024>::configure(meta)
+        }
+
+        fn synthesize(
+            &self,
+            (config, tables): Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            tables.load(&mut layouter)?;
+
+            let decompositions = if self.copy {
+                let cells = layouter.assign_region(
+                    || "Witness values",
+                    |mut region| {
+                        self.values
+                            .iter()
+                            .enumerate()
+                            .map(|(offset, value)| {
+                                region.assign_advice(
+                                    || "Witness value",
+                                    config.running_sum,
+                                    offset,
+                                    || *value,
+                                )
+                            })
+                            .collect::<Result<Vec<_>, _>>()
+                    },
+                )?;
+                config.copy_batch(
+                    layouter.namespace(|| "Decompose"),
+                    &cells,
+                    self.num_bits,
+                    self.short,
+                )?
+            } else {
+                config.assign_batch(
+                    layouter.namespace(|| "Decompose"),
+                    &self.values,
+                    self.num_bits,
+                    self.short,
+                )?
+            };
+            assert_eq!(decompositions.len(), self.values.len());
+
+            tables.finish()
+        }
+    }
+
+    #[test]
+    fn test_decompose_batch() {
+        let k = 11;
+
+        for num_bits in [20, 35, 64] {
+            let mask = if num_bits == 64 {
+                u64::MAX
+            } else {
+                (1 << num_bits) - 1
+            };
+            // spread over the range by a fixed odd multiplier
+            let values: Vec<u64> = (0..50u64)
+                .map(|i| i.wrapping_mul(0x9e37_79b9_7f4a_7c15) & mask)
+                .collect();
+
+            for short in [ShortRangeCheck::Shifted, ShortRangeCheck::Tagged] {
+                for copy in [false, true] {
+                    let verify = |values: &[u64]| {
+                        let circuit = BatchCircuit {
+                            values: values
+                                .iter()
+                                .map(|v| Value::known(Assigned::from(Fp::from(*v))))
+                                .collect(),
+                            num_bits,
+                            short,
+                            copy,
+                        };
+                        MockProver::run(k, &circuit, vec![])
+                            .unwrap()
+                            .verify()
+                            .is_ok()
+                    };
+
+                    assert!(verify(&values), "num_bits = {}", num_bits);
+
+                    // a single value out of range fails the whole batch
+                    if num_bits < 64 {
+                        let mut values = values.clone();
+                        values[17] = 1 << num_bits;
+                        assert!(!verify(&values), "num_bits = {}", num_bits);
+                    }
+                }
+            }
+        }
+    }
+
+    // Decompose a field element into `num_bits` bits and check that the chunks
+    // recombine to it
+    struct WideCircuit {
+        value: Value<Fp>,
+        num_bits: usize,
+        short: ShortRangeCheck,
+    }
+
+    impl Circuit<Fp> for WideCircuit {
+        type Config = (RangeCheckDecomposeConfig<Fp, 10, 1024>, TableRegistry);
+        type FloorPlanner = V1;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                value: Value::unknown(),
+                num_bits: self.num_bits,
+                short: self.short,
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            RangeCheckDecomposeCircuit::<Fp, 10, 1024>::configure(meta)
+        }
+
+        fn synthesize(
+            &self,
+            (config, tables): Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            tables.load(&mut layouter)?;
+
+            let decompositions = config.assign_batch(
+                layouter.namespace(|| "Decompose"),
+                &[self.value.map(Assigned::from)],
+                self.num_bits,
+                self.short,
+            )?;
+
+            let chunks = &decompositions[0].chunks;
+            assert_eq!(chunks.len(), (self.num_bits + 9) / 10);
+            let two_pow_k = Fp::from(1024);
+            let value = chunks
+                .iter()
+                .rev()
+                .fold(Value::known(Fp::from(0)), |acc, c| {
+                    acc.zip(c.value())
+                        .map(|(acc, c)| acc * two_pow_k + c.evaluate())
+                });
+            value
+                .zip(self.value)
+                .assert_if_known(|(value, expected)| value == expected);
+
+            tables.finish()
+        }
+    }
+
+    #[test]
+    fn test_decompose_wide() {
+        let k = 11;
+
+        for num_bits in [128, 200, 254] {
+            // The largest num_bits-bit value, or p - 1 when every element of
+            // the field fits in num_bits bits
+            let two_pow_n = Fp::from(2).pow_vartime([num_bits as u64]);
+            let max = if num_bits < Fp::NUM_BITS as usize {
+                two_pow_n - Fp::from(1)
+            } else {
+                -Fp::from(1)
+            };
+            // every third bit set, below the top one
+            let mixed = (0..num_bits - 1).fold(Fp::from(0), |acc, i| {
+                acc.double() + Fp::from((i % 3 == 0) as u64)
+            });
+
+            for short in [ShortRangeCheck::Shifted, ShortRangeCheck::Tagged] {
+                let verify = |value| {
+                    let circuit = WideCircuit {
+                        value: Value::known(value),
+                        num_bits,
+                        short,
+                    };
+                    MockProver::run(k, &circuit, vec![])
+                        .unwrap()
+                        .verify()
+                        .is_ok()
+                };
+
+                assert!(verify(Fp::from(0)), "num_bits = {}", num_bits);
+                assert!(verify(mixed), "num_bits = {}", num_bits);
+                assert!(verify(max), "num_bits = {}", num_bits);
+                if num_bits < Fp::NUM_BITS as usize {
+                    assert!(!verify(two_pow_n), "num_bits = {}", num_bits);
+                }
+            }
+        }
+    }
+
+    // Check `value` with range_check_4, the tagged lookup of range_check_3 and
+    // the lookup of range_check_2, all configured against one registry loaded
+    // `loads` times
+    struct SharedTablesCircuit {
+        value: Value<u64>,
+        num_bits: Value<u8>,
+        loads: usize,
+        assign: bool,
+    }
+
+    impl Circuit<Fp> for SharedTablesCircuit {
+        type Config = (
+            RangeCheckDecomposeConfig<Fp, 10, 1024>,
+            range_check_3::RangeCheckConfig<Fp, 1024, 10>,
+            range_check_2::RangeCheckConfig<Fp, 8, 1024>,
+            TableRegistry,
+        );
+        type FloorPlanner = V1;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                value: Value::unknown(),
+                num_bits: Value::unknown(),
+                loads: self.loads,
+                assign: self.assign,
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            let constants = meta.fixed_column();
+            meta.enable_constant(constants);
+
+            let [running_sum, chunk, num_bits] = [(); 3].map(|_| meta.advice_column());
+            let mut tables = TableRegistry::new();
+
+            (
+                RangeCheckDecomposeConfig::configure(meta, running_sum, chunk, &mut tables),
+                range_check_3::RangeCheckChip::configure(meta, num_bits, chunk, &mut tables),
+                range_check_2::RangeCheckChip::configure(meta, chunk, &mut tables),
+                tables,
+            )
+        }
+
+        fn synthesize(
+            &self,
+            (decompose, tagged, lookup, tables): Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            for _ in 0..self.loads {
+                tables.load(&mut layouter)?;
+            }
+            if !self.assign {
+                return tables.finish();
+            }
+
+            let value = self.value.map(|v| Assigned::from(Fp::from(v)));
+            let cell = layouter.assign_region(
+                || "Witness value",
+                |mut region| region.assign_advice(|| "value", decompose.running_sum, 0, || value),
+            )?;
+            decompose.assign(layouter.namespace(|| "Decompose"), cell, 10)?;
+
+            range_check_3::RangeCheckChip::construct(tagged).assign_lookup_table(
+                layouter.namespace(|| "Tagged lookup"),
+                value,
+                self.num_bits,
+            )?;
+            range_check_2::RangeCheckChip::construct(lookup)
+                .assign_lookup_table(layouter.namespace(|| "Lookup"), value)?;
+
+            tables.finish()
+        }
+    }
+
+    #[test]
+    fn test_shared_tables() {
+        let k = 11;
+
+        // range_check_3 and range_check_4 share the tagged 10-bit table
+        let mut meta = ConstraintSystem::<Fp>::default();
+        let (.., tables) = SharedTablesCircuit::configure(&mut meta);
+        assert_eq!(
+            tables.tables(),
+            vec![
+                RangeTable::Tagged { num_bits: 10 },
+                RangeTable::Values { range: 1024 }
+            ]
+        );
+
+        let circuit = |loads, assign| SharedTablesCircuit {
+            value: Value::known(700),
+            num_bits: Value::known(10),
+            loads,
+            assign,
+        };
+        let verify = |value, num_bits| {
+            let circuit = SharedTablesCircuit {
+                value: Value::known(value),
+                num_bits: Value::known(num_bits),
+                loads: 1,
+                assign: true,
+            };
+            MockProver::run(k, &circuit, vec![])
+                .unwrap()
+                .verify()
+                .is_ok()
+        };
+        for (value, num_bits) in [(0, 1), (1, 1), (700, 10), (1023, 10)] {
+            assert!(verify(value, num_bits));
+        }
+        assert!(!verify(700, 9));
+        assert!(!verify(1024, 11));
+
+        // a chip assigned before its table is loaded fails synthesis, as do
+        // loading the tables twice and a table that is never loaded
+        for (loads, assign) in [(0, true), (2, true), (0, false)] {
+            assert!(matches!(
+                MockProver::run(k, &circuit(loads, assign), vec![]),
+                Err(Error::Synthesis)
+            ));
+        }
+        // the V1 floor planner synthesizes twice with the same registry
+        assert!(MockProver::run(k, &circuit(1, false), vec![]).is_ok());
+    }
+
+    #[test]
+    fn range_check_4_constrained() {
+        // two full chunks and a short one
+        let circuit = RangeCheckDecomposeCircuit::<Fp, 10, 1024> {
+            value: Value::known(Assigned::from(Fp::from(0x1abcdef))),
+            num_bits: 25,
+        };
+        assert_constrained(11, &circuit, vec![], &[]);
+    }
+
+    // NUM_BITS is a const generic, so each tested NUM_BITS is instantiated here
+    fn accepts(lookup_bits: usize, num_bits: usize, value: i128) -> bool {
+        fn run<const NUM_BITS: usize, const RANGE: usize>(num_bits: usize, value: i128) -> bool {
+            let circuit = RangeCheckDecomposeCircuit::<Fp, NUM_BITS, RANGE> {
+                value: Value::known(Assigned::from(field(value))),
+                num_bits,
+            };
+            MockProver::run(11, &circuit, vec![])
+                .unwrap()
+                .verify()
+                .is_ok()
+        }
+
+        match lookup_bits {
+            4 => run::<4, 16>(num_bits, value),
+            8 => run::<8, 256>(num_bits, value),
+            10 => run::<10, 1024>(num_bits, value),
+            _ => unreachable!(),
+        }
+    }
+
+    // NUM_BITS, num_bits, and a value to decompose. A num_bits below NUM_BITS
+    // only has a short chunk, and a multiple of NUM_BITS none.
+    fn inputs() -> impl Strategy<Value = (usize, usize, i128)> {
+        (prop::sample::select(vec![4usize, 8, 10]), 1..=100usize).prop_flat_map(
+            |(lookup_bits, num_bits)| (Just(lookup_bits), Just(num_bits), values(1 << num_bits)),
+        )
+    }
+
+    proptest! {
+        #[test]
+        fn range_check_4_property((lookup_bits, num_bits, value) in inputs()) {
+            prop_assert_eq!(
+                accepts(lookup_bits, num_bits, value),
+                in_range(value, 1 << num_bits)
+            );
+        }
+    }
+
     #[cfg(feature = "dev-graph")]
     #[test]
     fn print_decompose_4() {
@@ -108,7 +679,7 @@ mod tests {
             value: Value::unknown(),
             num_bits: NUM_BITS,
         };
-        halo2_proofs_zcash::dev::CircuitLayout::default()
+        crate::backend::dev::CircuitLayout::default()
             .render(K, &circuit, &root)
             .unwrap();
     }
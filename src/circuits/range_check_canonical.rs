@@ -0,0 +1,249 @@
+use crate::backend::{
+    circuit::{floor_planner::V1, Layouter, Value},
+    plonk::{Assigned, Circuit, ConstraintSystem, Error},
+    Field,
+};
+use crate::chips::range_check_canonical::{CanonicalDecomposeChip, CanonicalDecomposeConfig};
+use crate::chips::table_registry::TableRegistry;
+
+#[derive(Clone)]
+pub struct CanonicalDecomposeCircuit<F: Field, const NUM_BITS: usize, const RANGE: usize> {
+    pub value: Value<Assigned<F>>,
+}
+
+impl<F: Field, const NUM_BITS: usize, const RANGE: usize> Circuit<F>
+    for CanonicalDecomposeCircuit<F, NUM_BITS, RANGE>
+{
+    type Config = (CanonicalDecomposeConfig<F, NUM_BITS, RANGE>, TableRegistry);
+    type FloorPlanner = V1;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            value: Value::unknown(),
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        // Fixed column for constants
+        let constants = meta.fixed_column();
+        meta.enable_constant(constants);
+
+        let advice = [(); 4].map(|_| meta.advice_column());
+        let mut tables = TableRegistry::new();
+
+        let config = CanonicalDecomposeChip::configure(meta, advice, &mut tables);
+        (config, tables)
+    }
+
+    fn synthesize(
+        &self,
+        (config, tables): Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        tables.load(&mut layouter)?;
+
+        let chip = CanonicalDecomposeChip::construct(config);
+        chip.assign(layouter.namespace(|| "value"), self.value)?;
+
+        tables.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CanonicalDecomposeCircuit;
+    use crate::backend::{
+        circuit::{floor_planner::V1, Layouter, Value},
+        dev::MockProver,
+        ff::Field as _,
+        plonk::{Assigned, Circuit, ConstraintSystem, Error},
+        Field, Fp,
+    };
+    use crate::chips::range_check_canonical::{CanonicalDecomposeChip, CanonicalDecomposeConfig};
+    use crate::chips::table_registry::TableRegistry;
+    use crate::circuits::under_constrained::assert_constrained;
+
+    const K: u32 = 11;
+    const NUM_BITS: usize = 10;
+    const RANGE: usize = 1024;
+
+    type Chip = CanonicalDecomposeChip<Fp, NUM_BITS, RANGE>;
+
+    // Decompose `value` with a given top bit, and optionally pin the chunks to
+    // the little-endian `bits`
+    struct TopCircuit {
+        value: Value<Fp>,
+        top: Value<bool>,
+        bits: Option<Vec<bool>>,
+    }
+
+    impl Circuit<Fp> for TopCircuit {
+        type Config = (CanonicalDecomposeConfig<Fp, NUM_BITS, RANGE>, TableRegistry);
+        type FloorPlanner = V1;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                value: Value::unknown(),
+                top: Value::unknown(),
+                bits: self.bits.clone(),
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            CanonicalDecomposeCircuit::<Fp, NUM_BITS, RANGE>::configure(meta)
+        }
+
+        fn synthesize(
+            &self,
+            (config, tables): Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            tables.load(&mut layouter)?;
+
+            let chip = Chip::construct(config);
+            let decomposition = chip.assign_with_top(
+                layouter.namespace(|| "value"),
+                self.value.map(Assigned::from),
+                self.top,
+            )?;
+
+            let bits = match &self.bits {
+                Some(bits) => bits,
+                None => return tables.finish(),
+            };
+            let (h, ..) = Chip::modulus();
+
+            layouter.assign_region(
+                || "Check bits",
+                |mut region| {
+                    for (chunk, bits) in decomposition
+                        .low
+                        .chunks
+                        .iter()
+                        .zip(bits[..h].chunks(NUM_BITS))
+                    {
+                        let expected = bits
+                            .iter()
+                            .rev()
+                            .fold(Fp::zero(), |acc, b| acc.double() + Fp::from(*b as u64));
+                        region.constrain_constant(chunk.cell(), expected)?;
+                    }
+                    region.constrain_constant(decomposition.top.cell(), Fp::from(bits[h] as u64))
+                },
+            )?;
+
+            tables.finish()
+        }
+    }
+
+    fn verify(value: Fp, top: bool, pin_bits: bool) -> bool {
+        let circuit = TopCircuit {
+            value: Value::known(value),
+            top: Value::known(top),
+            bits: pin_bits.then(|| value.le_bits()),
+        };
+        MockProver::run(K, &circuit, vec![])
+            .unwrap()
+            .verify()
+            .is_ok()
+    }
+
+    // p = 2^254 + t with a 126-bit t for Pallas
+    #[cfg(feature = "backend-zcash")]
+    const MODULUS_BITS: (usize, usize) = (254, 126);
+    // p = 2^253 + t with a 253-bit t for bn256
+    #[cfg(feature = "backend-pse")]
+    const MODULUS_BITS: (usize, usize) = (253, 253);
+
+    #[test]
+    fn test_canonical_modulus() {
+        let (h, two_pow_h, m, two_pow_m_minus_t) = Chip::modulus();
+        assert_eq!((h, m), MODULUS_BITS);
+        assert_eq!(two_pow_h, Fp::from(2u64).pow_vartime([h as u64]));
+
+        let t = -two_pow_h;
+        assert_eq!(
+            two_pow_m_minus_t + t,
+            Fp::from(2u64).pow_vartime([m as u64])
+        );
+    }
+
+    #[test]
+    fn test_canonical_decompose() {
+        let (h, two_pow_h, ..) = Chip::modulus();
+        let t = -two_pow_h;
+        assert_eq!(two_pow_h + t, Fp::zero());
+
+        let values = [
+            Fp::zero(),
+            Fp::one(),
+            Fp::from(u64::MAX),
+            // mixed bits below and with the top bit
+            two_pow_h - Fp::from(0x0123_4567_89ab_cdef),
+            two_pow_h + Fp::from(0x0123_4567_89ab_cdef),
+            // 2^h - 1, 2^h and 2^h + t - 1 = p - 1
+            two_pow_h - Fp::one(),
+            two_pow_h,
+            two_pow_h + t - Fp::one(),
+        ];
+
+        for value in values {
+            let top = value.le_bits()[h];
+            assert!(verify(value, top, true), "{:?}", value);
+
+            let circuit = CanonicalDecomposeCircuit::<Fp, NUM_BITS, RANGE> {
+                value: Value::known(Assigned::from(value)),
+            };
+            MockProver::run(K, &circuit, vec![])
+                .unwrap()
+                .assert_satisfied();
+        }
+    }
+
+    // `value + p` has the same bits mod p but is not canonical
+    #[test]
+    fn test_canonical_decompose_rejects_value_plus_p() {
+        let (_, two_pow_h, ..) = Chip::modulus();
+        let t = -two_pow_h;
+
+        // up to the largest value with value + t < 2^h
+        for value in [Fp::zero(), Fp::from(5u64), two_pow_h - t - Fp::one()] {
+            // value + p = (value + t) + 2^h
+            assert!(!verify(value, true, false), "{:?}", value);
+            assert!(verify(value, false, false), "{:?}", value);
+        }
+
+        // p - 1 with top = 0 leaves low = p - 1, which is h + 1 bits
+        assert!(!verify(-Fp::one(), false, false));
+    }
+
+    #[test]
+    fn range_check_canonical_constrained() {
+        // p - 1, with its top bit set
+        let circuit = CanonicalDecomposeCircuit::<Fp, NUM_BITS, RANGE> {
+            value: Value::known(Assigned::from(-Fp::from(1))),
+        };
+        assert_constrained(K, &circuit, vec![], &[]);
+    }
+
+    #[cfg(feature = "dev-graph")]
+    #[test]
+    fn plot_range_check_canonical() {
+        use plotters::{backend::BitMapBackend, drawing::IntoDrawingArea, style::WHITE};
+        use std::path::Path;
+
+        let filename = Path::new("./devgraphs/range-check-canonical-layout.png");
+        let title = "Range Check Canonical Layout";
+
+        let root = BitMapBackend::new(filename, (1024, 3096)).into_drawing_area();
+        root.fill(&WHITE).unwrap();
+        let root = root.titled(title, ("sans-serif", 60)).unwrap();
+
+        let circuit = CanonicalDecomposeCircuit::<Fp, NUM_BITS, RANGE> {
+            value: Value::unknown(),
+        };
+        crate::backend::dev::CircuitLayout::default()
+            .render(K, &circuit, &root)
+            .unwrap();
+    }
+}
@@ -0,0 +1,185 @@
+use crate::backend::{
+    circuit::{floor_planner::V1, Layouter, Value},
+    plonk::{Assigned, Circuit, ConstraintSystem, Error},
+    Field,
+};
+use crate::chips::range_check_interval::{RangeCheckIntervalChip, RangeCheckIntervalConfig};
+use crate::chips::table_registry::TableRegistry;
+
+#[derive(Clone)]
+pub struct RangeCheckIntervalCircuit<
+    F: Field,
+    const SMALL_RANGE: usize,
+    const NUM_BITS: usize,
+    const RANGE: usize,
+> {
+    pub value: Value<Assigned<F>>,
+    pub lo: u64,
+    pub hi: u64,
+}
+
+impl<F: Field, const SMALL_RANGE: usize, const NUM_BITS: usize, const RANGE: usize> Circuit<F>
+    for RangeCheckIntervalCircuit<F, SMALL_RANGE, NUM_BITS, RANGE>
+{
+    type Config = (
+        RangeCheckIntervalConfig<F, SMALL_RANGE, NUM_BITS, RANGE>,
+        TableRegistry,
+    );
+    type FloorPlanner = V1;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            value: Value::unknown(),
+            lo: self.lo,
+            hi: self.hi,
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        // Fixed column for constants
+        let constants = meta.fixed_column();
+        meta.enable_constant(constants);
+
+        let value = meta.advice_column();
+        let diff = meta.advice_column();
+        let bound = meta.fixed_column();
+        let running_sum = meta.advice_column();
+        let mut tables = TableRegistry::new();
+
+        let config =
+            RangeCheckIntervalChip::configure(meta, value, diff, bound, running_sum, &mut tables);
+        (config, tables)
+    }
+
+    fn synthesize(
+        &self,
+        (config, tables): Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        tables.load(&mut layouter)?;
+
+        let chip = RangeCheckIntervalChip::construct(config);
+
+        chip.assign(layouter.namespace(|| "value"), self.value, self.lo, self.hi)?;
+
+        tables.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RangeCheckIntervalCircuit;
+    use crate::backend::{circuit::Value, dev::MockProver, plonk::Assigned, Fp};
+    use crate::chips::range_check_interval::RangeCheckIntervalChip;
+    use crate::circuits::under_constrained::assert_constrained;
+
+    const K: u32 = 9;
+    const SMALL_RANGE: usize = 8;
+    const NUM_BITS: usize = 8;
+    const RANGE: usize = 256;
+
+    type IntervalCircuit = RangeCheckIntervalCircuit<Fp, SMALL_RANGE, NUM_BITS, RANGE>;
+
+    fn verify(value: u64, lo: u64, hi: u64) -> bool {
+        let circuit = IntervalCircuit {
+            value: Value::known(Assigned::from(Fp::from(value))),
+            lo,
+            hi,
+        };
+
+        MockProver::run(K, &circuit, vec![])
+            .unwrap()
+            .verify()
+            .is_ok()
+    }
+
+    fn check(lo: u64, hi: u64) {
+        assert!(verify(lo, lo, hi));
+        assert!(verify(hi - 1, lo, hi));
+        assert!(verify(lo + (hi - lo) / 2, lo, hi));
+
+        if lo > 0 {
+            assert!(!verify(lo - 1, lo, hi));
+        }
+        assert!(!verify(hi, lo, hi));
+        assert!(!verify(hi + 1000, lo, hi));
+    }
+
+    #[test]
+    fn range_check_interval_small_span() {
+        check(3, 8);
+        check(0, 1);
+        check(100, 108);
+    }
+
+    #[test]
+    fn range_check_interval_large_span() {
+        // 18 <= age < 130
+        check(18, 130);
+        // 1000 <= amount <= 2^40
+        check(1000, (1 << 40) + 1);
+        // bounds that are not powers of two or aligned to chunks
+        check(12345, 12345 + 257);
+    }
+
+    // the value must not wrap around the field below lo
+    #[test]
+    fn range_check_interval_negative() {
+        let circuit = IntervalCircuit {
+            value: Value::known(Assigned::from(-Fp::from(1))),
+            lo: 0,
+            hi: 130,
+        };
+        assert!(MockProver::run(K, &circuit, vec![])
+            .unwrap()
+            .verify()
+            .is_err());
+    }
+
+    #[test]
+    fn range_check_interval_num_bits() {
+        type Chip = RangeCheckIntervalChip<Fp, SMALL_RANGE, NUM_BITS, RANGE>;
+
+        assert_eq!(Chip::num_bits(0, 1), 8);
+        assert_eq!(Chip::num_bits(18, 130), 8);
+        assert_eq!(Chip::num_bits(0, 256), 8);
+        assert_eq!(Chip::num_bits(0, 257), 16);
+        assert_eq!(Chip::num_bits(1000, (1 << 40) + 1), 48);
+    }
+
+    #[test]
+    fn range_check_interval_constrained() {
+        // a small span, then one decomposed to 16 bits
+        for (value, lo, hi) in [(5, 3, 8), (150, 100, 1000)] {
+            let circuit = IntervalCircuit {
+                value: Value::known(Assigned::from(Fp::from(value))),
+                lo,
+                hi,
+            };
+            assert_constrained(K, &circuit, vec![], &[]);
+        }
+    }
+
+    #[cfg(feature = "dev-graph")]
+    #[test]
+    fn plot_range_check_interval() {
+        use plotters::{backend::BitMapBackend, drawing::IntoDrawingArea, style::WHITE};
+        use std::path::Path;
+
+        let filename = Path::new("./devgraphs/range-check-interval-layout.png");
+        let title = "Range Check Interval Layout";
+
+        let root = BitMapBackend::new(filename, (1024, 3096)).into_drawing_area();
+        root.fill(&WHITE).unwrap();
+        let root = root.titled(title, ("sans-serif", 60)).unwrap();
+
+        let circuit = IntervalCircuit {
+            value: Value::unknown(),
+            lo: 18,
+            hi: 130,
+        };
+        crate::backend::dev::CircuitLayout::default()
+            .render(K, &circuit, &root)
+            .unwrap();
+    }
+}
@@ -0,0 +1,180 @@
+use crate::backend::{
+    circuit::{floor_planner::V1, Layouter, Value},
+    plonk::{Assigned, Circuit, Column, ConstraintSystem, Error, Instance},
+    Field,
+};
+use crate::chips::range_check_signed::{SignedRangeCheckChip, SignedRangeCheckConfig};
+use crate::chips::table_registry::TableRegistry;
+
+#[derive(Debug, Clone)]
+pub struct SignedRangeCheckCircuitConfig<F: Field, const NUM_BITS: usize, const RANGE: usize> {
+    signed: SignedRangeCheckConfig<F, NUM_BITS, RANGE>,
+    instance: Column<Instance>,
+    tables: TableRegistry,
+}
+
+/// Constrains `value` to [-2^{num_bits-1}, 2^{num_bits-1}) and exposes its sign
+/// and absolute value as the public inputs `[sign, abs]`.
+#[derive(Clone)]
+pub struct SignedRangeCheckCircuit<F: Field, const NUM_BITS: usize, const RANGE: usize> {
+    pub value: Value<Assigned<F>>,
+    pub num_bits: usize,
+}
+
+impl<F: Field, const NUM_BITS: usize, const RANGE: usize>
+    SignedRangeCheckCircuit<F, NUM_BITS, RANGE>
+{
+    /// The public inputs `[sign, abs]` of a signed value.
+    pub fn instance(value: i64) -> Vec<F> {
+        vec![F::from((value < 0) as u64), F::from(value.unsigned_abs())]
+    }
+}
+
+impl<F: Field, const NUM_BITS: usize, const RANGE: usize> Circuit<F>
+    for SignedRangeCheckCircuit<F, NUM_BITS, RANGE>
+{
+    type Config = SignedRangeCheckCircuitConfig<F, NUM_BITS, RANGE>;
+    type FloorPlanner = V1;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            value: Value::unknown(),
+            num_bits: self.num_bits,
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        // Fixed column for constants
+        let constants = meta.fixed_column();
+        meta.enable_constant(constants);
+
+        let advice = [(); 4].map(|_| meta.advice_column());
+        let instance = meta.instance_column();
+        meta.enable_equality(instance);
+        let mut tables = TableRegistry::new();
+
+        SignedRangeCheckCircuitConfig {
+            signed: SignedRangeCheckChip::configure(meta, advice, &mut tables),
+            instance,
+            tables,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        config.tables.load(&mut layouter)?;
+
+        let chip = SignedRangeCheckChip::construct(config.signed);
+        let signed = chip.assign(layouter.namespace(|| "value"), self.value, self.num_bits)?;
+
+        layouter.constrain_instance(signed.sign.cell(), config.instance, 0)?;
+        layouter.constrain_instance(signed.abs.cell(), config.instance, 1)?;
+
+        config.tables.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SignedRangeCheckCircuit;
+    use crate::backend::{circuit::Value, dev::MockProver, plonk::Assigned, Fp};
+    use crate::circuits::under_constrained::assert_constrained;
+
+    const K: u32 = 11;
+    const NUM_BITS: usize = 10;
+    const RANGE: usize = 1024;
+
+    type SignedCircuit = SignedRangeCheckCircuit<Fp, NUM_BITS, RANGE>;
+
+    // x as a field element, with negative values wrapping to p - |x|
+    fn signed(x: i64) -> Fp {
+        if x < 0 {
+            -Fp::from(x.unsigned_abs())
+        } else {
+            Fp::from(x as u64)
+        }
+    }
+
+    fn verify(x: i64, num_bits: usize, instance: Vec<Fp>) -> bool {
+        let circuit = SignedCircuit {
+            value: Value::known(Assigned::from(signed(x))),
+            num_bits,
+        };
+        MockProver::run(K, &circuit, vec![instance])
+            .unwrap()
+            .verify()
+            .is_ok()
+    }
+
+    #[test]
+    fn range_check_signed() {
+        // i32, and an 8-bit value shorter than a single chunk
+        for num_bits in [32, 8] {
+            let half = 1i64 << (num_bits - 1);
+
+            for x in [0, 1, -1, 42, -42, half - 1, -half, half / 3, -half / 3] {
+                assert!(
+                    verify(x, num_bits, SignedCircuit::instance(x)),
+                    "{} in {} bits",
+                    x,
+                    num_bits
+                );
+            }
+
+            for x in [half, -half - 1, 3 * half, -3 * half] {
+                assert!(
+                    !verify(x, num_bits, SignedCircuit::instance(x)),
+                    "{} in {} bits",
+                    x,
+                    num_bits
+                );
+            }
+        }
+    }
+
+    // the sign and the absolute value are unique
+    #[test]
+    fn range_check_signed_outputs() {
+        for x in [0i64, 5, -5, i32::MIN as i64] {
+            let [sign, abs] = [0, 1].map(|i| SignedCircuit::instance(x)[i]);
+
+            assert!(!verify(x, 32, vec![Fp::one() - sign, abs]), "{}", x);
+            assert!(!verify(x, 32, vec![sign, abs + Fp::one()]), "{}", x);
+            assert!(!verify(x, 32, vec![sign, -abs]) || x == 0, "{}", x);
+        }
+    }
+
+    #[test]
+    fn range_check_signed_constrained() {
+        let circuit = SignedCircuit {
+            value: Value::known(Assigned::from(signed(-42))),
+            num_bits: 32,
+        };
+        assert_constrained(K, &circuit, vec![SignedCircuit::instance(-42)], &[]);
+    }
+
+    #[cfg(feature = "dev-graph")]
+    #[test]
+    fn plot_range_check_signed() {
+        use plotters::{backend::BitMapBackend, drawing::IntoDrawingArea, style::WHITE};
+        use std::path::Path;
+
+        let filename = Path::new("./devgraphs/range-check-signed-layout.png");
+        let title = "Range Check Signed Layout";
+
+        let root = BitMapBackend::new(filename, (1024, 3096)).into_drawing_area();
+        root.fill(&WHITE).unwrap();
+        let root = root.titled(title, ("sans-serif", 60)).unwrap();
+
+        let circuit = SignedCircuit {
+            value: Value::unknown(),
+            num_bits: 32,
+        };
+        crate::backend::dev::CircuitLayout::default()
+            .render(K, &circuit, &root)
+            .unwrap();
+    }
+}
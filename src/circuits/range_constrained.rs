@@ -0,0 +1,225 @@
+use crate::backend::{
+    circuit::{floor_planner::V1, Layouter, Value},
+    plonk::{Assigned, Circuit, ConstraintSystem, Error},
+    Field,
+};
+use crate::chips::range_check_4::RangeCheckDecomposeConfig;
+use crate::chips::range_constrained::{ArithmeticConfig, RangeConstrained};
+use crate::chips::table_registry::TableRegistry;
+
+#[derive(Debug, Clone)]
+pub struct RangeConstrainedConfig<F: Field, const NUM_BITS: usize, const RANGE: usize> {
+    pub decompose: RangeCheckDecomposeConfig<F, NUM_BITS, RANGE>,
+    pub arithmetic: ArithmeticConfig,
+    pub tables: TableRegistry,
+}
+
+/// Range-checks `a` and `b` to `num_bits` with range_check_4, adds and
+/// multiplies them, and decomposes the results again to the widened bounds.
+#[derive(Default)]
+pub struct RangeConstrainedCircuit<F: Field, const NUM_BITS: usize, const RANGE: usize> {
+    pub a: Value<Assigned<F>>,
+    pub b: Value<Assigned<F>>,
+    pub num_bits: (usize, usize),
+}
+
+impl<F: Field, const NUM_BITS: usize, const RANGE: usize>
+    RangeConstrainedCircuit<F, NUM_BITS, RANGE>
+{
+    /// Returns a + b and a * b.
+    pub fn assign(
+        &self,
+        config: &RangeConstrainedConfig<F, NUM_BITS, RANGE>,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(RangeConstrained<F>, RangeConstrained<F>), Error> {
+        let [a, b] = [(self.a, self.num_bits.0), (self.b, self.num_bits.1)].map(
+            |(value, num_bits)| -> Result<_, Error> {
+                let value = layouter.assign_region(
+                    || "Witness value",
+                    |mut region| {
+                        region.assign_advice(|| "value", config.decompose.running_sum, 0, || value)
+                    },
+                )?;
+                let decomposition = config.decompose.assign(
+                    layouter.namespace(|| "Decompose value"),
+                    value,
+                    num_bits,
+                )?;
+                Ok(decomposition.range_constrained().unwrap())
+            },
+        );
+        let (a, b) = (a?, b?);
+
+        let sum = a.add(layouter.namespace(|| "a + b"), &config.arithmetic, &b)?;
+        let product = a.mul(layouter.namespace(|| "a * b"), &config.arithmetic, &b)?;
+
+        for result in [&sum, &product] {
+            config.decompose.assign(
+                layouter.namespace(|| "Decompose result"),
+                result.cell().clone(),
+                result.num_bits(),
+            )?;
+        }
+
+        Ok((sum, product))
+    }
+}
+
+impl<F: Field, const NUM_BITS: usize, const RANGE: usize> Circuit<F>
+    for RangeConstrainedCircuit<F, NUM_BITS, RANGE>
+{
+    type Config = RangeConstrainedConfig<F, NUM_BITS, RANGE>;
+    type FloorPlanner = V1;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            a: Value::unknown(),
+            b: Value::unknown(),
+            num_bits: self.num_bits,
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        // Fixed column for constants
+        let constants = meta.fixed_column();
+        meta.enable_constant(constants);
+
+        let advice = [(); 3].map(|_| meta.advice_column());
+        let mut tables = TableRegistry::new();
+
+        // The arithmetic shares the decomposition columns
+        RangeConstrainedConfig {
+            decompose: RangeCheckDecomposeConfig::configure(
+                meta,
+                advice[0],
+                advice[1],
+                &mut tables,
+            ),
+            arithmetic: ArithmeticConfig::configure(meta, advice),
+            tables,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        config.tables.load(&mut layouter)?;
+
+        self.assign(&config, layouter)?;
+        config.tables.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RangeConstrainedCircuit, RangeConstrainedConfig};
+    use crate::backend::{
+        circuit::{floor_planner::V1, Layouter, Value},
+        dev::MockProver,
+        plonk::{Assigned, Circuit, ConstraintSystem, Error},
+        Fp,
+    };
+    use crate::circuits::under_constrained::assert_constrained;
+
+    const K: u32 = 11;
+    const NUM_BITS: usize = 10;
+    const RANGE: usize = 1024; // 10-bit value
+
+    type Arithmetic = RangeConstrainedCircuit<Fp, NUM_BITS, RANGE>;
+
+    fn circuit(a: u64, b: u64, num_bits: (usize, usize)) -> Arithmetic {
+        Arithmetic {
+            a: Value::known(Assigned::from(Fp::from(a))),
+            b: Value::known(Assigned::from(Fp::from(b))),
+            num_bits,
+        }
+    }
+
+    fn verify(a: u64, b: u64, num_bits: (usize, usize)) -> bool {
+        MockProver::run(K, &circuit(a, b, num_bits), vec![])
+            .unwrap()
+            .verify()
+            .is_ok()
+    }
+
+    #[test]
+    fn range_constrained() {
+        for num_bits in [(20, 12), (12, 20), (10, 10), (7, 23)] {
+            let max = |n: usize| (1 << n) - 1;
+            let (a, b) = (max(num_bits.0), max(num_bits.1));
+
+            assert!(verify(a, b, num_bits));
+            assert!(verify(0, b, num_bits));
+            assert!(verify(0x5a5a_5a5a & a, 0xa5a5_a5a5 & b, num_bits));
+
+            // out-of-range inputs
+            assert!(!verify(a + 1, b, num_bits));
+            assert!(!verify(a, b + 1, num_bits));
+        }
+    }
+
+    // Checks the bounds and values of a + b and a * b
+    struct BoundsCircuit(Arithmetic, (usize, usize), (u64, u64));
+
+    impl Circuit<Fp> for BoundsCircuit {
+        type Config = RangeConstrainedConfig<Fp, NUM_BITS, RANGE>;
+        type FloorPlanner = V1;
+
+        fn without_witnesses(&self) -> Self {
+            Self(self.0.without_witnesses(), self.1, self.2)
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            Arithmetic::configure(meta)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            config.tables.load(&mut layouter)?;
+
+            let (sum, product) = self.0.assign(&config, layouter)?;
+            assert_eq!((sum.num_bits(), product.num_bits()), self.1);
+            for (result, expected) in [(&sum, self.2 .0), (&product, self.2 .1)] {
+                result.value().assert_if_known(|v| *v == Fp::from(expected));
+                assert_eq!(result.value_u64().is_some(), result.num_bits() <= 64);
+                if let Some(value) = result.value_u64() {
+                    value.assert_if_known(|v| *v == expected);
+                }
+            }
+            assert!(sum.num_bits_cell().is_none());
+            config.tables.finish()
+        }
+    }
+
+    #[test]
+    fn range_constrained_bounds() {
+        let (a, b) = ((1 << 20) - 1, (1 << 12) - 1);
+        let circuit = BoundsCircuit(circuit(a, b, (20, 12)), (21, 32), (a + b, a * b));
+        MockProver::run(K, &circuit, vec![])
+            .unwrap()
+            .assert_satisfied();
+
+        // a 70-bit product has no u64 value, even when it is small
+        let circuit = BoundsCircuit(circuit(3, 5, (40, 30)), (41, 70), (8, 15));
+        MockProver::run(K, &circuit, vec![])
+            .unwrap()
+            .assert_satisfied();
+    }
+
+    #[test]
+    fn range_constrained_rejects_wrapping_bounds() {
+        // a 256-bit product could wrap around the modulus
+        assert!(MockProver::run(K, &circuit(1, 1, (128, 128)), vec![]).is_err());
+    }
+
+    #[test]
+    fn range_constrained_constrained() {
+        let circuit = circuit((1 << 20) - 1, 42, (20, 12));
+        assert_constrained(K, &circuit, vec![], &[]);
+    }
+}
@@ -0,0 +1,39 @@
+use proptest::prelude::*;
+
+use crate::backend::Fp;
+
+// out-of-range values go up to 2^120, well below the modulus
+const MAX_BITS: u32 = 120;
+
+/// Values to range-check against `[0, range)`: values in the range, at both
+/// of its ends, just above it, far above it, and negative values, which wrap
+/// around the modulus. Each shrinks towards the end of the range it lies
+/// next to.
+pub(crate) fn values(range: i128) -> impl Strategy<Value = i128> {
+    assert!(range > 0 && range < 1 << MAX_BITS);
+
+    prop_oneof![
+        3 => 0..range,
+        1 => prop::sample::select(vec![0, range - 1, range, range + 1]),
+        1 => range..2 * range,
+        1 => range..1 << MAX_BITS,
+        1 => (1..1i128 << MAX_BITS).prop_map(|v| -v),
+    ]
+}
+
+/// Whether `value` lies in `[0, range)`.
+pub(crate) fn in_range(value: i128, range: i128) -> bool {
+    (0..range).contains(&value)
+}
+
+/// `value` as a field element, `p - |value|` if it is negative.
+pub(crate) fn field(value: i128) -> Fp {
+    let abs = value.unsigned_abs();
+    let two_pow_64 = Fp::from(u64::MAX) + Fp::from(1);
+    let x = Fp::from((abs >> 64) as u64) * two_pow_64 + Fp::from(abs as u64);
+    if value < 0 {
+        -x
+    } else {
+        x
+    }
+}
@@ -0,0 +1,331 @@
+use std::cell::{Cell, RefCell};
+use std::fmt;
+use std::marker::PhantomData;
+
+use rand::rngs::OsRng;
+
+use crate::backend::{
+    circuit::{Layouter, Value},
+    dev::MockProver,
+    ff::Field,
+    plonk::{
+        Advice, Any, Assigned, Assignment, Circuit, Column, ConstraintSystem, Error, Fixed,
+        FloorPlanner, Instance, Selector,
+    },
+    Fp,
+};
+#[cfg(feature = "backend-pse")]
+use crate::backend::{plonk::Challenge, SyncDeps};
+
+/// An advice cell whose value can be changed without failing `verify`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct AdviceCell {
+    pub region: String,
+    pub annotation: String,
+    pub column: usize,
+    pub row: usize,
+}
+
+impl fmt::Display for AdviceCell {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "\"{}\" in region \"{}\" (advice column {}, row {})",
+            self.annotation, self.region, self.column, self.row
+        )
+    }
+}
+
+thread_local! {
+    // the advice assignment to overwrite, counted in synthesis order
+    static TARGET: Cell<Option<usize>> = Cell::new(None);
+    // the advice cells assigned by the last synthesis
+    static CELLS: RefCell<Vec<AdviceCell>> = RefCell::new(vec![]);
+}
+
+/// Synthesize `circuit` with a valid witness, then overwrite each of its
+/// advice cells in turn with a random value. Returns the cells whose mutation
+/// still passes `MockProver::verify`.
+///
+/// Only one cell is mutated at a time, so freedom spanning several cells is
+/// missed: if a cell can only change together with another, e.g. a row of a
+/// table whose gates tie it to its neighbours, no single mutation verifies.
+/// An empty result does not mean the circuit is fully constrained.
+pub(crate) fn unconstrained_cells<C: Circuit<Fp>>(
+    k: u32,
+    circuit: &C,
+    instance: Vec<Vec<Fp>>,
+) -> Vec<AdviceCell> {
+    let circuit = Mutated::Witnessed(circuit);
+
+    TARGET.with(|target| target.set(None));
+    MockProver::run(k, &circuit, instance.clone())
+        .unwrap()
+        .assert_satisfied();
+    let cells = CELLS.with(|cells| cells.take());
+
+    let unconstrained = cells
+        .iter()
+        .enumerate()
+        .filter(|(index, _)| {
+            TARGET.with(|target| target.set(Some(*index)));
+            MockProver::run(k, &circuit, instance.clone())
+                .unwrap()
+                .verify()
+                .is_ok()
+        })
+        .map(|(_, cell)| cell.clone())
+        .collect();
+    TARGET.with(|target| target.set(None));
+
+    unconstrained
+}
+
+/// Panics unless the unconstrained cells of `circuit` are exactly those
+/// matching an `(region, annotation)` pair of `allowed`.
+pub(crate) fn assert_constrained<C: Circuit<Fp>>(
+    k: u32,
+    circuit: &C,
+    instance: Vec<Vec<Fp>>,
+    allowed: &[(&str, &str)],
+) {
+    let is_allowed = |cell: &AdviceCell, (region, annotation): &(&str, &str)| {
+        cell.region == *region && cell.annotation == *annotation
+    };
+
+    let cells = unconstrained_cells(k, circuit, instance);
+    let unexpected: Vec<_> = cells
+        .iter()
+        .filter(|&cell| !allowed.iter().any(|entry| is_allowed(cell, entry)))
+        .map(|cell| cell.to_string())
+        .collect();
+    let unused: Vec<_> = allowed
+        .iter()
+        .filter(|&entry| !cells.iter().any(|cell| is_allowed(cell, entry)))
+        .collect();
+
+    assert!(
+        unexpected.is_empty(),
+        "unconstrained advice cells:\n  {}",
+        unexpected.join("\n  ")
+    );
+    assert!(
+        unused.is_empty(),
+        "allowed cells that are constrained: {:?}",
+        unused
+    );
+}
+
+// The circuit under test, synthesized by `MutatingPlanner`
+enum Mutated<'a, C> {
+    Witnessed(&'a C),
+    WithoutWitnesses(C),
+}
+
+impl<'a, C> Mutated<'a, C> {
+    fn circuit(&self) -> &C {
+        match self {
+            Mutated::Witnessed(circuit) => circuit,
+            Mutated::WithoutWitnesses(circuit) => circuit,
+        }
+    }
+}
+
+impl<'a, F: Field, C: Circuit<F>> Circuit<F> for Mutated<'a, C> {
+    type Config = C::Config;
+    type FloorPlanner = MutatingPlanner<C::FloorPlanner>;
+
+    fn without_witnesses(&self) -> Self {
+        Mutated::WithoutWitnesses(self.circuit().without_witnesses())
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        C::configure(meta)
+    }
+
+    fn synthesize(&self, config: Self::Config, layouter: impl Layouter<F>) -> Result<(), Error> {
+        self.circuit().synthesize(config, layouter)
+    }
+}
+
+// Lays the circuit out with `P`, recording its advice cells in `CELLS` and
+// overwriting the one picked by `TARGET`
+struct MutatingPlanner<P>(PhantomData<P>);
+
+impl<P: FloorPlanner> FloorPlanner for MutatingPlanner<P> {
+    #[cfg(feature = "backend-zcash")]
+    fn synthesize<F: Field, CS: Assignment<F>, C: Circuit<F>>(
+        cs: &mut CS,
+        circuit: &C,
+        config: C::Config,
+        constants: Vec<Column<Fixed>>,
+    ) -> Result<(), Error> {
+        synthesize_mutated::<P, _, _, _>(cs, circuit, config, constants)
+    }
+
+    #[cfg(feature = "backend-pse")]
+    fn synthesize<F: Field, CS: Assignment<F> + SyncDeps, C: Circuit<F>>(
+        cs: &mut CS,
+        circuit: &C,
+        config: C::Config,
+        constants: Vec<Column<Fixed>>,
+    ) -> Result<(), Error> {
+        synthesize_mutated::<P, _, _, _>(cs, circuit, config, constants)
+    }
+}
+
+fn synthesize_mutated<P: FloorPlanner, F: Field, CS: Assignment<F>, C: Circuit<F>>(
+    cs: &mut CS,
+    circuit: &C,
+    config: C::Config,
+    constants: Vec<Column<Fixed>>,
+) -> Result<(), Error> {
+    let mut cs = MutatingAssignment {
+        cs,
+        target: TARGET.with(|target| target.get()),
+        region: String::new(),
+        cells: vec![],
+    };
+    P::synthesize(&mut cs, circuit, config, constants)?;
+
+    CELLS.with(|cells| *cells.borrow_mut() = cs.cells);
+    Ok(())
+}
+
+struct MutatingAssignment<'a, CS> {
+    cs: &'a mut CS,
+    target: Option<usize>,
+    region: String,
+    cells: Vec<AdviceCell>,
+}
+
+impl<'a, F: Field, CS: Assignment<F>> Assignment<F> for MutatingAssignment<'a, CS> {
+    fn enter_region<NR, N>(&mut self, name_fn: N)
+    where
+        NR: Into<String>,
+        N: FnOnce() -> NR,
+    {
+        let name: String = name_fn().into();
+        self.region = name.clone();
+        self.cs.enter_region(|| name)
+    }
+
+    #[cfg(feature = "backend-pse")]
+    fn annotate_column<A, AR>(&mut self, annotation: A, column: Column<Any>)
+    where
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        self.cs.annotate_column(annotation, column)
+    }
+
+    fn exit_region(&mut self) {
+        self.cs.exit_region()
+    }
+
+    fn enable_selector<A, AR>(
+        &mut self,
+        annotation: A,
+        selector: &Selector,
+        row: usize,
+    ) -> Result<(), Error>
+    where
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        self.cs.enable_selector(annotation, selector, row)
+    }
+
+    fn query_instance(&self, column: Column<Instance>, row: usize) -> Result<Value<F>, Error> {
+        self.cs.query_instance(column, row)
+    }
+
+    fn assign_advice<V, VR, A, AR>(
+        &mut self,
+        annotation: A,
+        column: Column<Advice>,
+        row: usize,
+        to: V,
+    ) -> Result<(), Error>
+    where
+        V: FnOnce() -> Value<VR>,
+        VR: Into<Assigned<F>>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        let annotation: String = annotation().into();
+        let mutate = self.target == Some(self.cells.len());
+        self.cells.push(AdviceCell {
+            region: self.region.clone(),
+            annotation: annotation.clone(),
+            column: column.index(),
+            row,
+        });
+
+        self.cs.assign_advice(
+            || annotation,
+            column,
+            row,
+            || {
+                let value = to().map(|v| v.into());
+                if mutate {
+                    value.map(|_| Assigned::from(F::random(OsRng)))
+                } else {
+                    value
+                }
+            },
+        )
+    }
+
+    fn assign_fixed<V, VR, A, AR>(
+        &mut self,
+        annotation: A,
+        column: Column<Fixed>,
+        row: usize,
+        to: V,
+    ) -> Result<(), Error>
+    where
+        V: FnOnce() -> Value<VR>,
+        VR: Into<Assigned<F>>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        self.cs.assign_fixed(annotation, column, row, to)
+    }
+
+    fn copy(
+        &mut self,
+        left_column: Column<Any>,
+        left_row: usize,
+        right_column: Column<Any>,
+        right_row: usize,
+    ) -> Result<(), Error> {
+        self.cs.copy(left_column, left_row, right_column, right_row)
+    }
+
+    fn fill_from_row(
+        &mut self,
+        column: Column<Fixed>,
+        row: usize,
+        to: Value<Assigned<F>>,
+    ) -> Result<(), Error> {
+        self.cs.fill_from_row(column, row, to)
+    }
+
+    #[cfg(feature = "backend-pse")]
+    fn get_challenge(&self, challenge: Challenge) -> Value<F> {
+        self.cs.get_challenge(challenge)
+    }
+
+    fn push_namespace<NR, N>(&mut self, name_fn: N)
+    where
+        NR: Into<String>,
+        N: FnOnce() -> NR,
+    {
+        self.cs.push_namespace(name_fn)
+    }
+
+    fn pop_namespace(&mut self, gadget_name: Option<String>) {
+        self.cs.pop_namespace(gadget_name)
+    }
+}
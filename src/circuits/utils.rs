@@ -0,0 +1,67 @@
+use crate::backend::{
+    plonk::{Circuit, ConstraintSystem, Error},
+    Field,
+};
+
+/// Smallest `k` such that `2^k` rows fit `rows` usable rows of circuit `C`,
+/// taking into account the rows reserved by the backend for blinding factors.
+pub(crate) fn min_k<F: Field, C: Circuit<F>>(rows: usize) -> u32 {
+    let mut cs = ConstraintSystem::default();
+    C::configure(&mut cs);
+
+    let total_rows = rows + cs.blinding_factors() + 1;
+    total_rows.next_power_of_two().trailing_zeros()
+}
+
+/// Compute F(n) of the Fibonacci sequence starting with F(1) = a and F(2) = b.
+pub(crate) fn fibonacci<F: Field>(a: F, b: F, n: usize) -> F {
+    assert!(n >= 1);
+
+    let (mut prev, mut cur) = (a, b);
+    if n == 1 {
+        return prev;
+    }
+    for _ in 2..n {
+        let next = prev + cur;
+        prev = cur;
+        cur = next;
+    }
+    cur
+}
+
+/// Check that an instance vector `[F(1), F(2), F(n)]` is consistent with the
+/// target index `n` of a Fibonacci circuit.
+pub(crate) fn check_fibonacci_instance<F: Field>(instance: &[F], n: usize) -> Result<(), Error> {
+    if instance.len() != 3 {
+        return Err(Error::InvalidInstances);
+    }
+
+    if fibonacci(instance[0], instance[1], n) != instance[2] {
+        return Err(Error::InvalidInstances);
+    }
+
+    Ok(())
+}
+
+/// Compute f(n) of `f(n) = c_1 * f(n - 1) + ... + c_k * f(n - k)` starting with
+/// `f(1), ..., f(k) = initial`.
+pub(crate) fn linear_recurrence<F: Field + From<u64>>(
+    initial: &[F],
+    coefficients: &[u64],
+    n: usize,
+) -> F {
+    assert_eq!(initial.len(), coefficients.len());
+    assert!(n >= 1);
+
+    let mut terms = initial.to_vec();
+    while terms.len() < n {
+        let next = coefficients
+            .iter()
+            .zip(terms.iter().rev())
+            .map(|(c, term)| F::from(*c) * term)
+            .reduce(|acc, term| acc + term)
+            .unwrap();
+        terms.push(next);
+    }
+    terms[n - 1]
+}
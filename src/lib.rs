@@ -1,2 +1,4 @@
-mod chips;
-mod circuits;
+pub mod backend;
+pub mod chips;
+pub mod circuits;
+pub mod proving;
@@ -0,0 +1,289 @@
+use halo2_proofs::{
+    halo2curves::bn256::{Bn256, Fr, G1Affine},
+    plonk::{
+        create_proof, keygen_pk, keygen_vk, verify_proof, Circuit, Error, ProvingKey, VerifyingKey,
+    },
+    poly::kzg::{
+        commitment::{KZGCommitmentScheme, ParamsKZG},
+        multiopen::{ProverGWC, ProverSHPLONK, VerifierGWC, VerifierSHPLONK},
+        strategy::SingleStrategy,
+    },
+    transcript::{
+        Blake2bRead, Blake2bWrite, Challenge255, Keccak256Read, Keccak256Write,
+        TranscriptReadBuffer, TranscriptWriterBuffer,
+    },
+};
+use rand::rngs::OsRng;
+
+/// Multiopen argument used to batch the polynomial openings of a proof.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MultiOpen {
+    Shplonk,
+    Gwc,
+}
+
+/// Hash function backing the Fiat-Shamir transcript.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TranscriptHash {
+    Blake2b,
+    Keccak256,
+}
+
+/// Generate (insecure, locally sampled) KZG parameters for circuits of size 2^k.
+pub fn setup(k: u32) -> ParamsKZG<Bn256> {
+    ParamsKZG::<Bn256>::setup(k, OsRng)
+}
+
+pub fn keygen<C: Circuit<Fr>>(
+    params: &ParamsKZG<Bn256>,
+    circuit: &C,
+) -> Result<ProvingKey<G1Affine>, Error> {
+    let vk = keygen_vk(params, circuit)?;
+    keygen_pk(params, vk, circuit)
+}
+
+/// Create a proof for `circuit`, with one slice of `instances` per instance column.
+pub fn prove<C: Circuit<Fr>>(
+    params: &ParamsKZG<Bn256>,
+    pk: &ProvingKey<G1Affine>,
+    circuit: C,
+    instances: &[&[Fr]],
+    multiopen: MultiOpen,
+    hash: TranscriptHash,
+) -> Result<Vec<u8>, Error> {
+    match (multiopen, hash) {
+        (MultiOpen::Shplonk, TranscriptHash::Blake2b) => {
+            let mut transcript = Blake2bWrite::<_, G1Affine, Challenge255<_>>::init(vec![]);
+            create_proof::<
+                KZGCommitmentScheme<Bn256>,
+                ProverSHPLONK<'_, Bn256>,
+                Challenge255<G1Affine>,
+                _,
+                Blake2bWrite<Vec<u8>, G1Affine, Challenge255<G1Affine>>,
+                _,
+            >(params, pk, &[circuit], &[instances], OsRng, &mut transcript)?;
+            Ok(transcript.finalize())
+        }
+        (MultiOpen::Shplonk, TranscriptHash::Keccak256) => {
+            let mut transcript = Keccak256Write::<_, G1Affine, Challenge255<_>>::init(vec![]);
+            create_proof::<
+                KZGCommitmentScheme<Bn256>,
+                ProverSHPLONK<'_, Bn256>,
+                Challenge255<G1Affine>,
+                _,
+                Keccak256Write<Vec<u8>, G1Affine, Challenge255<G1Affine>>,
+                _,
+            >(params, pk, &[circuit], &[instances], OsRng, &mut transcript)?;
+            Ok(transcript.finalize())
+        }
+        (MultiOpen::Gwc, TranscriptHash::Blake2b) => {
+            let mut transcript = Blake2bWrite::<_, G1Affine, Challenge255<_>>::init(vec![]);
+            create_proof::<
+                KZGCommitmentScheme<Bn256>,
+                ProverGWC<'_, Bn256>,
+                Challenge255<G1Affine>,
+                _,
+                Blake2bWrite<Vec<u8>, G1Affine, Challenge255<G1Affine>>,
+                _,
+            >(params, pk, &[circuit], &[instances], OsRng, &mut transcript)?;
+            Ok(transcript.finalize())
+        }
+        (MultiOpen::Gwc, TranscriptHash::Keccak256) => {
+            let mut transcript = Keccak256Write::<_, G1Affine, Challenge255<_>>::init(vec![]);
+            create_proof::<
+                KZGCommitmentScheme<Bn256>,
+                ProverGWC<'_, Bn256>,
+                Challenge255<G1Affine>,
+                _,
+                Keccak256Write<Vec<u8>, G1Affine, Challenge255<G1Affine>>,
+                _,
+            >(params, pk, &[circuit], &[instances], OsRng, &mut transcript)?;
+            Ok(transcript.finalize())
+        }
+    }
+}
+
+/// Verify a proof created by [`prove`] with the same `multiopen` and `hash`.
+pub fn verify(
+    params: &ParamsKZG<Bn256>,
+    vk: &VerifyingKey<G1Affine>,
+    proof: &[u8],
+    instances: &[&[Fr]],
+    multiopen: MultiOpen,
+    hash: TranscriptHash,
+) -> Result<(), Error> {
+    let strategy = SingleStrategy::new(params);
+
+    match (multiopen, hash) {
+        (MultiOpen::Shplonk, TranscriptHash::Blake2b) => {
+            let mut transcript = Blake2bRead::<_, G1Affine, Challenge255<_>>::init(proof);
+            verify_proof::<
+                KZGCommitmentScheme<Bn256>,
+                VerifierSHPLONK<'_, Bn256>,
+                Challenge255<G1Affine>,
+                Blake2bRead<&[u8], G1Affine, Challenge255<G1Affine>>,
+                SingleStrategy<'_, Bn256>,
+            >(params, vk, strategy, &[instances], &mut transcript)
+        }
+        (MultiOpen::Shplonk, TranscriptHash::Keccak256) => {
+            let mut transcript = Keccak256Read::<_, G1Affine, Challenge255<_>>::init(proof);
+            verify_proof::<
+                KZGCommitmentScheme<Bn256>,
+                VerifierSHPLONK<'_, Bn256>,
+                Challenge255<G1Affine>,
+                Keccak256Read<&[u8], G1Affine, Challenge255<G1Affine>>,
+                SingleStrategy<'_, Bn256>,
+            >(params, vk, strategy, &[instances], &mut transcript)
+        }
+        (MultiOpen::Gwc, TranscriptHash::Blake2b) => {
+            let mut transcript = Blake2bRead::<_, G1Affine, Challenge255<_>>::init(proof);
+            verify_proof::<
+                KZGCommitmentScheme<Bn256>,
+                VerifierGWC<'_, Bn256>,
+                Challenge255<G1Affine>,
+                Blake2bRead<&[u8], G1Affine, Challenge255<G1Affine>>,
+                SingleStrategy<'_, Bn256>,
+            >(params, vk, strategy, &[instances], &mut transcript)
+        }
+        (MultiOpen::Gwc, TranscriptHash::Keccak256) => {
+            let mut transcript = Keccak256Read::<_, G1Affine, Challenge255<_>>::init(proof);
+            verify_proof::<
+                KZGCommitmentScheme<Bn256>,
+                VerifierGWC<'_, Bn256>,
+                Challenge255<G1Affine>,
+                Keccak256Read<&[u8], G1Affine, Challenge255<G1Affine>>,
+                SingleStrategy<'_, Bn256>,
+            >(params, vk, strategy, &[instances], &mut transcript)
+        }
+    }
+}
+
+// The circuits are built over the field of the `backend-pse` backend
+#[cfg(all(test, feature = "backend-pse"))]
+mod tests {
+    use halo2_proofs::{
+        circuit::Value,
+        halo2curves::bn256::Fr as Fp,
+        plonk::{Assigned, Circuit},
+    };
+
+    use super::{keygen, prove, setup, verify, MultiOpen, TranscriptHash};
+    use crate::circuits::{
+        fib_1::FibonacciCircuit1, fib_2::FibonacciCircuit2, fib_3::FibonacciCircuit3,
+        fib_public_index::FibonacciPublicIndexCircuit, fib_range_check::FibRangeCheckCircuit,
+        range_check_4::RangeCheckDecomposeCircuit, utils::fibonacci,
+    };
+
+    const SCHEMES: [(MultiOpen, TranscriptHash); 4] = [
+        (MultiOpen::Shplonk, TranscriptHash::Blake2b),
+        (MultiOpen::Shplonk, TranscriptHash::Keccak256),
+        (MultiOpen::Gwc, TranscriptHash::Blake2b),
+        (MultiOpen::Gwc, TranscriptHash::Keccak256),
+    ];
+
+    // Prove F[10] = 55 honestly, then check that a tampered public output is
+    // rejected both when it is proven and when it is only claimed to the verifier.
+    fn prove_and_verify<C: Circuit<Fp>>(k: u32, circuit: impl Fn() -> C) {
+        let params = setup(k);
+        let pk = keygen(&params, &circuit()).unwrap();
+
+        let public_input = [Fp::from(1), Fp::from(1), Fp::from(55)];
+        let mut tampered_input = public_input;
+        tampered_input[2] += Fp::one();
+        let (public_input, tampered_input) = (&[&public_input[..]], &[&tampered_input[..]]);
+
+        let vk = pk.get_vk();
+
+        for (multiopen, hash) in SCHEMES {
+            let proof = prove(&params, &pk, circuit(), public_input, multiopen, hash).unwrap();
+            verify(&params, vk, &proof, public_input, multiopen, hash).unwrap();
+            assert!(verify(&params, vk, &proof, tampered_input, multiopen, hash).is_err());
+
+            let proof = prove(&params, &pk, circuit(), tampered_input, multiopen, hash).unwrap();
+            assert!(verify(&params, vk, &proof, tampered_input, multiopen, hash).is_err());
+        }
+    }
+
+    #[test]
+    fn kzg_fib_1() {
+        let k = FibonacciCircuit1::<Fp>::new(10).k();
+        prove_and_verify(k, || FibonacciCircuit1::new(10));
+    }
+
+    #[test]
+    fn kzg_fib_2() {
+        let k = FibonacciCircuit2::<Fp>::new(10).k();
+        prove_and_verify(k, || FibonacciCircuit2::new(10));
+    }
+
+    #[test]
+    fn kzg_fib_3() {
+        let k = FibonacciCircuit3::<Fp>::new(10).k();
+        prove_and_verify(k, || FibonacciCircuit3::new(10));
+    }
+
+    // F(10) = 55 is an 8-bit value
+    #[test]
+    fn kzg_fib_range_check() {
+        let k = FibRangeCheckCircuit::<Fp, 8, 256>::new(10, 8).k();
+        prove_and_verify(k, || FibRangeCheckCircuit::<Fp, 8, 256>::new(10, 8));
+    }
+
+    #[test]
+    fn kzg_range_check_4() {
+        const K: u32 = 11;
+        const NUM_BITS: usize = 10;
+        const RANGE: usize = 1024; // 10-bit value
+
+        let circuit = |value: u64| RangeCheckDecomposeCircuit::<Fp, NUM_BITS, RANGE> {
+            value: Value::known(Assigned::from(Fp::from(value))),
+            num_bits: 20,
+        };
+        let params = setup(K);
+        let pk = keygen(&params, &circuit(0)).unwrap();
+        let vk = pk.get_vk();
+
+        for (multiopen, hash) in SCHEMES {
+            let proof = prove(&params, &pk, circuit((1 << 20) - 1), &[], multiopen, hash).unwrap();
+            verify(&params, vk, &proof, &[], multiopen, hash).unwrap();
+
+            // Out-of-range `value = 2^20` leaves a non-zero z_C. Depending on
+            // which argument catches it, either proving or verifying fails.
+            let rejected = match prove(&params, &pk, circuit(1 << 20), &[], multiopen, hash) {
+                Ok(proof) => verify(&params, vk, &proof, &[], multiopen, hash).is_err(),
+                Err(_) => true,
+            };
+            assert!(rejected);
+        }
+    }
+
+    // a single proving key for every n <= N_MAX
+    #[test]
+    fn kzg_fib_public_index() {
+        let circuit = FibonacciPublicIndexCircuit::<Fp, 20>::new();
+        let params = setup(circuit.k());
+        let pk = keygen(&params, &circuit).unwrap();
+        let vk = pk.get_vk();
+        let (multiopen, hash) = SCHEMES[0];
+
+        for n in [1, 7, 20] {
+            let out = fibonacci(Fp::from(1), Fp::from(1), n);
+            let public_input = vec![Fp::from(1), Fp::from(1), Fp::from(n as u64), out];
+
+            let proof = prove(
+                &params,
+                &pk,
+                circuit.without_witnesses(),
+                &[&public_input[..]],
+                multiopen,
+                hash,
+            )
+            .unwrap();
+            verify(&params, vk, &proof, &[&public_input[..]], multiopen, hash).unwrap();
+
+            let mut other_n = public_input.clone();
+            other_n[2] += Fp::one();
+            assert!(verify(&params, vk, &proof, &[&other_n[..]], multiopen, hash).is_err());
+        }
+    }
+}
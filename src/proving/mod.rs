@@ -0,0 +1,3 @@
+pub mod ipa;
+pub mod kzg;
+pub mod serialization;
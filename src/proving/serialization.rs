@@ -0,0 +1,639 @@
+//! On-disk layout shared by every file written by this module:
+//!
+//!     | magic "H2PG" | version | kind | backend | k (u32 LE) | name_len | name | payload |
+//!
+//! `kind` tells params, verifying keys, proofs and public inputs apart, so that
+//! reading a file as the wrong kind fails early instead of producing garbage.
+//!
+//! An IPA verifying key is stored as a digest, prefixed by the zcash halo2 rev
+//! it was computed with:
+//!
+//!     | header | rev_len | rev | digest (64 bytes) |
+
+use std::io::{self, Read, Write};
+
+use ff::PrimeField;
+use halo2_proofs::{
+    halo2curves::{
+        bn256::{Bn256, Fr, G1Affine},
+        serde::SerdeObject,
+    },
+    plonk::{Circuit, VerifyingKey},
+    poly::{commitment::Params, kzg::commitment::ParamsKZG},
+    SerdeFormat,
+};
+use halo2_proofs_zcash::{
+    pasta::{EqAffine, Fp},
+    plonk::{keygen_vk, VerifyingKey as IpaVerifyingKey},
+    poly::commitment::Params as IpaParams,
+};
+
+const MAGIC: [u8; 4] = *b"H2PG";
+const VERSION: u8 = 1;
+// Far above the proofs of any circuit here, which are a few kilobytes
+const MAX_PROOF_LEN: usize = 1 << 20;
+// The rev of `halo2_proofs_zcash` in Cargo.toml, which the IPA key digest
+// depends on
+const HALO2_ZCASH_REV: &str = "a898d65ae3ad3d41987666f6a03cfc15edae01c4";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backend {
+    /// PSE `halo2_proofs` over bn256
+    Kzg,
+    /// zcash `halo2_proofs` over the Pasta curves
+    Ipa,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    Params,
+    VerifyingKey,
+    Proof,
+    Instances,
+}
+
+/// Names the circuit, `k` and backend a file belongs to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Header {
+    pub circuit: String,
+    pub k: u32,
+    pub backend: Backend,
+}
+
+impl Header {
+    pub fn new(circuit: &str, k: u32, backend: Backend) -> Self {
+        Self {
+            circuit: circuit.to_string(),
+            k,
+            backend,
+        }
+    }
+
+    fn write<W: Write>(&self, writer: &mut W, kind: Kind) -> io::Result<()> {
+        let name = self.circuit.as_bytes();
+        if name.len() > u8::MAX as usize {
+            return Err(invalid_data("circuit name too long"));
+        }
+
+        writer.write_all(&MAGIC)?;
+        writer.write_all(&[VERSION, kind as u8, self.backend as u8])?;
+        writer.write_all(&self.k.to_le_bytes())?;
+        writer.write_all(&[name.len() as u8])?;
+        writer.write_all(name)
+    }
+
+    fn read<R: Read>(reader: &mut R, kind: Kind) -> io::Result<Self> {
+        let mut magic = [0u8; 4];
+        reader.read_exact(&mut magic)?;
+        if magic != MAGIC {
+            return Err(invalid_data("not a halo2-playground file"));
+        }
+
+        let mut tags = [0u8; 3];
+        reader.read_exact(&mut tags)?;
+        let [version, file_kind, backend] = tags;
+        if version != VERSION {
+            return Err(invalid_data(&format!("unsupported version {}", version)));
+        }
+        if file_kind != kind as u8 {
+            return Err(invalid_data(&format!("expected {:?} file", kind)));
+        }
+        let backend = match backend {
+            0 => Backend::Kzg,
+            1 => Backend::Ipa,
+            _ => return Err(invalid_data("unknown backend")),
+        };
+
+        let k = read_u32(reader)?;
+
+        let mut name_len = [0u8; 1];
+        reader.read_exact(&mut name_len)?;
+        let mut name = vec![0u8; name_len[0] as usize];
+        reader.read_exact(&mut name)?;
+        let circuit = String::from_utf8(name).map_err(|_| invalid_data("circuit name"))?;
+
+        Ok(Self {
+            circuit,
+            k,
+            backend,
+        })
+    }
+
+    /// Check that a header read from disk is the one the caller expects.
+    pub fn expect(&self, expected: &Header) -> io::Result<()> {
+        if self != expected {
+            return Err(invalid_data(&format!(
+                "expected {:?}, found {:?}",
+                expected, self
+            )));
+        }
+        Ok(())
+    }
+}
+
+fn invalid_data(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg.to_string())
+}
+
+fn read_u32<R: Read>(reader: &mut R) -> io::Result<u32> {
+    let mut bytes = [0u8; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+// The params store `k` themselves, which has to match the one in the header.
+fn expect_k(header: &Header, k: u32) -> io::Result<()> {
+    if header.k != k {
+        return Err(invalid_data(&format!(
+            "params for k = {} in a file for k = {}",
+            k, header.k
+        )));
+    }
+    Ok(())
+}
+
+fn write_len<W: Write>(writer: &mut W, len: usize) -> io::Result<()> {
+    let len = u32::try_from(len).map_err(|_| invalid_data("length does not fit in u32"))?;
+    writer.write_all(&len.to_le_bytes())
+}
+
+// Proofs are plain transcripts, so they are stored the same way for both backends.
+
+pub fn write_proof<W: Write>(writer: &mut W, header: &Header, proof: &[u8]) -> io::Result<()> {
+    header.write(writer, Kind::Proof)?;
+    write_len(writer, proof.len())?;
+    writer.write_all(proof)
+}
+
+pub fn read_proof<R: Read>(reader: &mut R) -> io::Result<(Header, Vec<u8>)> {
+    let header = Header::read(reader, Kind::Proof)?;
+    let len = read_u32(reader)? as usize;
+    if len > MAX_PROOF_LEN {
+        return Err(invalid_data(&format!("proof of {} bytes is too long", len)));
+    }
+    let mut proof = vec![0u8; len];
+    reader.read_exact(&mut proof)?;
+    Ok((header, proof))
+}
+
+// KZG
+
+pub fn write_params_kzg<W: Write>(
+    writer: &mut W,
+    header: &Header,
+    params: &ParamsKZG<Bn256>,
+) -> io::Result<()> {
+    header.write(writer, Kind::Params)?;
+    params.write(writer)
+}
+
+pub fn read_params_kzg<R: Read>(reader: &mut R) -> io::Result<(Header, ParamsKZG<Bn256>)> {
+    let header = Header::read(reader, Kind::Params)?;
+    let params = ParamsKZG::<Bn256>::read(reader)?;
+    expect_k(&header, params.k())?;
+    Ok((header, params))
+}
+
+pub fn write_vk_kzg<W: Write>(
+    writer: &mut W,
+    header: &Header,
+    vk: &VerifyingKey<G1Affine>,
+) -> io::Result<()> {
+    header.write(writer, Kind::VerifyingKey)?;
+    vk.write(writer, SerdeFormat::RawBytes)
+}
+
+/// The constraint system is not part of the serialized key, so the circuit
+/// type that produced it has to be named when reading it back.
+pub fn read_vk_kzg<R: Read, C: Circuit<Fr>>(
+    reader: &mut R,
+) -> io::Result<(Header, VerifyingKey<G1Affine>)> {
+    let header = Header::read(reader, Kind::VerifyingKey)?;
+    let vk = VerifyingKey::<G1Affine>::read::<_, C>(reader, SerdeFormat::RawBytes)?;
+    Ok((header, vk))
+}
+
+/// Public inputs are stored per instance column.
+pub fn write_instances_kzg<W: Write>(
+    writer: &mut W,
+    header: &Header,
+    instances: &[&[Fr]],
+) -> io::Result<()> {
+    header.write(writer, Kind::Instances)?;
+    write_len(writer, instances.len())?;
+    for column in instances {
+        write_len(writer, column.len())?;
+        for value in column.iter() {
+            value.write_raw(writer)?;
+        }
+    }
+    Ok(())
+}
+
+pub fn read_instances_kzg<R: Read>(reader: &mut R) -> io::Result<(Header, Vec<Vec<Fr>>)> {
+    let header = Header::read(reader, Kind::Instances)?;
+    let num_columns = read_u32(reader)?;
+    let instances = (0..num_columns)
+        .map(|_| {
+            let len = read_u32(reader)?;
+            (0..len).map(|_| Fr::read_raw(reader)).collect()
+        })
+        .collect::<io::Result<_>>()?;
+    Ok((header, instances))
+}
+
+// IPA
+
+pub fn write_params_ipa<W: Write>(
+    writer: &mut W,
+    header: &Header,
+    params: &IpaParams<EqAffine>,
+) -> io::Result<()> {
+    header.write(writer, Kind::Params)?;
+    params.write(writer)
+}
+
+pub fn read_params_ipa<R: Read>(reader: &mut R) -> io::Result<(Header, IpaParams<EqAffine>)> {
+    let header = Header::read(reader, Kind::Params)?;
+    let params = IpaParams::<EqAffine>::read(reader)?;
+    // n = 2^k generators
+    expect_k(&header, params.get_g().len().trailing_zeros())?;
+    Ok((header, params))
+}
+
+// The zcash backend cannot serialize a verifying key, but it can rebuild one
+// deterministically from the params and the circuit. We only store a digest of
+// the key and check the rebuilt key against it.
+//
+// The fork keeps the fixed and permutation commitments of the key private, so
+// the digest hashes the `Debug` output of the pinned key instead, as halo2
+// itself does for the key's transcript representation. That output is only
+// stable for one halo2 version: the file records the halo2 rev next to the
+// digest, and a key written with another rev is rejected before the digest is
+// compared; it has to be regenerated with `setup`. `vk_digest_ipa_is_stable`
+// pins the digest of a known key.
+fn vk_digest_ipa(vk: &IpaVerifyingKey<EqAffine>) -> [u8; 64] {
+    let pinned = format!("{:?}", vk.pinned());
+    *blake2b_simd::blake2b(pinned.as_bytes()).as_array()
+}
+
+pub fn write_vk_ipa<W: Write>(
+    writer: &mut W,
+    header: &Header,
+    vk: &IpaVerifyingKey<EqAffine>,
+) -> io::Result<()> {
+    header.write(writer, Kind::VerifyingKey)?;
+    writer.write_all(&[HALO2_ZCASH_REV.len() as u8])?;
+    writer.write_all(HALO2_ZCASH_REV.as_bytes())?;
+    writer.write_all(&vk_digest_ipa(vk))
+}
+
+pub fn read_vk_ipa<R: Read, C: halo2_proofs_zcash::plonk::Circuit<Fp>>(
+    reader: &mut R,
+    params: &IpaParams<EqAffine>,
+    circuit: &C,
+) -> io::Result<(Header, IpaVerifyingKey<EqAffine>)> {
+    let header = Header::read(reader, Kind::VerifyingKey)?;
+
+    let mut rev_len = [0u8; 1];
+    reader.read_exact(&mut rev_len)?;
+    let mut rev = vec![0u8; rev_len[0] as usize];
+    reader.read_exact(&mut rev)?;
+    if rev != HALO2_ZCASH_REV.as_bytes() {
+        return Err(invalid_data(&format!(
+            "verifying key digest from halo2 rev {}, expected {}: regenerate it with setup",
+            String::from_utf8_lossy(&rev),
+            HALO2_ZCASH_REV
+        )));
+    }
+
+    let mut digest = [0u8; 64];
+    reader.read_exact(&mut digest)?;
+
+    let vk = keygen_vk(params, circuit)
+        .map_err(|e| invalid_data(&format!("failed to rebuild verifying key: {:?}", e)))?;
+    if vk_digest_ipa(&vk) != digest {
+        return Err(invalid_data("verifying key does not match the circuit"));
+    }
+    Ok((header, vk))
+}
+
+pub fn write_instances_ipa<W: Write>(
+    writer: &mut W,
+    header: &Header,
+    instances: &[&[Fp]],
+) -> io::Result<()> {
+    header.write(writer, Kind::Instances)?;
+    write_len(writer, instances.len())?;
+    for column in instances {
+        write_len(writer, column.len())?;
+        for value in column.iter() {
+            writer.write_all(&value.to_repr())?;
+        }
+    }
+    Ok(())
+}
+
+pub fn read_instances_ipa<R: Read>(reader: &mut R) -> io::Result<(Header, Vec<Vec<Fp>>)> {
+    let header = Header::read(reader, Kind::Instances)?;
+    let num_columns = read_u32(reader)?;
+    let instances = (0..num_columns)
+        .map(|_| {
+            let len = read_u32(reader)?;
+            (0..len)
+                .map(|_| {
+                    let mut repr = [0u8; 32];
+                    reader.read_exact(&mut repr)?;
+                    Option::<Fp>::from(Fp::from_repr(repr))
+                        .ok_or_else(|| invalid_data("field element"))
+                })
+                .collect()
+        })
+        .collect::<io::Result<_>>()?;
+    Ok((header, instances))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs::File,
+        io::{BufReader, BufWriter},
+        path::{Path, PathBuf},
+    };
+
+    use super::*;
+    use crate::proving::{ipa, kzg};
+
+    // `a^(2^powers)` by repeated squaring, exposed as the only public input.
+    // Written against each fork directly, so that both file formats are tested
+    // whichever backend the chips are built on.
+    macro_rules! square_circuit {
+        ($module:ident, $halo2:ident, $field:ty) => {
+            mod $module {
+                use $halo2::{
+                    circuit::{Layouter, SimpleFloorPlanner, Value},
+                    plonk::{Advice, Circuit, Column, ConstraintSystem, Error, Instance, Selector},
+                    poly::Rotation,
+                };
+
+                pub struct SquareCircuit {
+                    pub a: Value<$field>,
+                    pub powers: usize,
+                }
+
+                impl Circuit<$field> for SquareCircuit {
+                    type Config = (Column<Advice>, Column<Instance>, Selector);
+                    type FloorPlanner = SimpleFloorPlanner;
+
+                    fn without_witnesses(&self) -> Self {
+                        Self {
+                            a: Value::unknown(),
+                            powers: self.powers,
+                        }
+                    }
+
+                    fn configure(meta: &mut ConstraintSystem<$field>) -> Self::Config {
+                        let advice = meta.advice_column();
+                        let instance = meta.instance_column();
+                        let selector = meta.selector();
+                        meta.enable_equality(advice);
+                        meta.enable_equality(instance);
+
+                        meta.create_gate("square", |meta| {
+                            let s = meta.query_selector(selector);
+                            let a = meta.query_advice(advice, Rotation::cur());
+                            let square = meta.query_advice(advice, Rotation::next());
+                            vec![s * (a.clone() * a - square)]
+                        });
+
+                        (advice, instance, selector)
+                    }
+
+                    fn synthesize(
+                        &self,
+                        (advice, instance, selector): Self::Config,
+                        mut layouter: impl Layouter<$field>,
+                    ) -> Result<(), Error> {
+                        let out = layouter.assign_region(
+                            || "squares",
+                            |mut region| {
+                                let mut cell =
+                                    region.assign_advice(|| "a", advice, 0, || self.a)?;
+                                for row in 0..self.powers {
+                                    selector.enable(&mut region, row)?;
+                                    let square = cell.value().map(|a| *a * *a);
+                                    cell = region.assign_advice(
+                                        || "square",
+                                        advice,
+                                        row + 1,
+                                        || square,
+                                    )?;
+                                }
+                                Ok(cell)
+                            },
+                        )?;
+
+                        layouter.constrain_instance(out.cell(), instance, 0)
+                    }
+                }
+            }
+        };
+    }
+
+    square_circuit!(
+        kzg_square,
+        halo2_proofs,
+        halo2_proofs::halo2curves::bn256::Fr
+    );
+    square_circuit!(
+        ipa_square,
+        halo2_proofs_zcash,
+        halo2_proofs_zcash::pasta::Fp
+    );
+
+    const K: u32 = 4;
+
+    fn tmp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("halo2-playground-{}-{}", std::process::id(), name))
+    }
+
+    fn write_file(name: &str, f: impl FnOnce(&mut BufWriter<File>) -> io::Result<()>) -> PathBuf {
+        let path = tmp_path(name);
+        let mut writer = BufWriter::new(File::create(&path).unwrap());
+        f(&mut writer).unwrap();
+        writer.flush().unwrap();
+        path
+    }
+
+    fn open(path: &PathBuf) -> BufReader<File> {
+        BufReader::new(File::open(path).unwrap())
+    }
+
+    #[test]
+    fn round_trip_kzg() {
+        use halo2_proofs::circuit::Value;
+        use kzg_square::SquareCircuit;
+
+        let (multiopen, hash) = (kzg::MultiOpen::Shplonk, kzg::TranscriptHash::Blake2b);
+
+        let circuit = |a| SquareCircuit { a, powers: 3 };
+        let header = Header::new("square", K, Backend::Kzg);
+
+        let params = kzg::setup(K);
+        let pk = kzg::keygen(&params, &circuit(Value::unknown())).unwrap();
+        // 3^8
+        let public_input = vec![Fr::from(6561)];
+        let proof = kzg::prove(
+            &params,
+            &pk,
+            circuit(Value::known(Fr::from(3))),
+            &[&public_input[..]],
+            multiopen,
+            hash,
+        )
+        .unwrap();
+
+        let params_path = write_file("kzg.params", |w| write_params_kzg(w, &header, &params));
+        let vk_path = write_file("kzg.vk", |w| write_vk_kzg(w, &header, pk.get_vk()));
+        let proof_path = write_file("kzg.proof", |w| write_proof(w, &header, &proof));
+        let instances_path = write_file("kzg.instances", |w| {
+            write_instances_kzg(w, &header, &[&public_input[..]])
+        });
+
+        let (params_header, params) = read_params_kzg(&mut open(&params_path)).unwrap();
+        let (vk_header, vk) = read_vk_kzg::<_, SquareCircuit>(&mut open(&vk_path)).unwrap();
+        let (proof_header, proof) = read_proof(&mut open(&proof_path)).unwrap();
+        let (instances_header, instances) = read_instances_kzg(&mut open(&instances_path)).unwrap();
+
+        for h in [params_header, vk_header, proof_header, instances_header] {
+            h.expect(&header).unwrap();
+        }
+        assert_eq!(instances, vec![public_input]);
+
+        kzg::verify(&params, &vk, &proof, &[&instances[0][..]], multiopen, hash).unwrap();
+
+        // a proof is not a verifying key
+        assert!(read_vk_kzg::<_, SquareCircuit>(&mut open(&proof_path)).is_err());
+
+        for path in [params_path, vk_path, proof_path, instances_path] {
+            std::fs::remove_file(path).unwrap();
+        }
+    }
+
+    #[test]
+    fn round_trip_ipa() {
+        use halo2_proofs_zcash::circuit::Value;
+        use ipa_square::SquareCircuit;
+
+        let circuit = |a| SquareCircuit { a, powers: 3 };
+        let header = Header::new("square", K, Backend::Ipa);
+
+        let params = ipa::setup(K);
+        let pk = ipa::keygen(&params, &circuit(Value::unknown())).unwrap();
+        // 3^8
+        let public_input = vec![Fp::from(6561)];
+        let proof = ipa::prove(
+            &params,
+            &pk,
+            circuit(Value::known(Fp::from(3))),
+            &[&public_input[..]],
+        )
+        .unwrap();
+
+        let params_path = write_file("ipa.params", |w| write_params_ipa(w, &header, &params));
+        let vk_path = write_file("ipa.vk", |w| write_vk_ipa(w, &header, pk.get_vk()));
+        let proof_path = write_file("ipa.proof", |w| write_proof(w, &header, &proof));
+        let instances_path = write_file("ipa.instances", |w| {
+            write_instances_ipa(w, &header, &[&public_input[..]])
+        });
+
+        let (params_header, params) = read_params_ipa(&mut open(&params_path)).unwrap();
+        // the params are for k = 4 only
+        let wrong_k = Header::new(&header.circuit, K + 1, Backend::Ipa);
+        let wrong_k_path = write_file("ipa.wrong_k.params", |w| {
+            write_params_ipa(w, &wrong_k, &params)
+        });
+        assert!(read_params_ipa(&mut open(&wrong_k_path)).is_err());
+        let (vk_header, vk) =
+            read_vk_ipa(&mut open(&vk_path), &params, &circuit(Value::unknown())).unwrap();
+        let (proof_header, proof) = read_proof(&mut open(&proof_path)).unwrap();
+        let (instances_header, instances) = read_instances_ipa(&mut open(&instances_path)).unwrap();
+
+        for h in [params_header, vk_header, proof_header, instances_header] {
+            h.expect(&header).unwrap();
+        }
+        assert_eq!(instances, vec![public_input]);
+
+        ipa::verify(&params, &vk, &proof, &[&instances[0][..]]).unwrap();
+
+        // the stored key does not match a circuit with a different layout
+        let other = SquareCircuit {
+            a: Value::unknown(),
+            powers: 2,
+        };
+        assert!(read_vk_ipa(&mut open(&vk_path), &params, &other).is_err());
+
+        // nor a key digest computed with another halo2 rev
+        let mut bytes = std::fs::read(&vk_path).unwrap();
+        let rev = bytes
+            .windows(HALO2_ZCASH_REV.len())
+            .position(|window| window == HALO2_ZCASH_REV.as_bytes())
+            .unwrap();
+        bytes[rev] ^= 1;
+        assert!(read_vk_ipa(&mut &bytes[..], &params, &circuit(Value::unknown())).is_err());
+
+        for path in [
+            params_path,
+            wrong_k_path,
+            vk_path,
+            proof_path,
+            instances_path,
+        ] {
+            std::fs::remove_file(path).unwrap();
+        }
+    }
+
+    // The IPA key digest hashes the `Debug` output of the pinned key, which
+    // halo2 does not keep stable across revs. This pins the digest of a known
+    // key, so that a change of halo2 rev which changes it also has to update
+    // HALO2_ZCASH_REV. After such a change, rewrite the expected digest with
+    // `UPDATE_VK_DIGEST=1 cargo test vk_digest_ipa_is_stable`.
+    #[test]
+    fn vk_digest_ipa_is_stable() {
+        use halo2_proofs_zcash::circuit::Value;
+        use ipa_square::SquareCircuit;
+
+        let circuit = SquareCircuit {
+            a: Value::unknown(),
+            powers: 3,
+        };
+        let vk = keygen_vk(&ipa::setup(K), &circuit).unwrap();
+        let digest: String = vk_digest_ipa(&vk)
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect();
+
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("src/proving/testdata/ipa_vk_digest");
+        if std::env::var_os("UPDATE_VK_DIGEST").is_some() {
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(&path, format!("{} {}\n", HALO2_ZCASH_REV, digest)).unwrap();
+        }
+        let expected = std::fs::read_to_string(&path)
+            .expect("no expected digest, write it with UPDATE_VK_DIGEST=1");
+        assert_eq!(
+            expected.trim(),
+            format!("{} {}", HALO2_ZCASH_REV, digest),
+            "the IPA key digest changed, bump HALO2_ZCASH_REV"
+        );
+    }
+
+    #[test]
+    fn read_proof_rejects_long_length() {
+        let header = Header::new("fib-1", 4, Backend::Kzg);
+        let mut bytes = vec![];
+        header.write(&mut bytes, Kind::Proof).unwrap();
+        bytes.extend_from_slice(&u32::MAX.to_le_bytes());
+
+        assert!(read_proof(&mut &bytes[..]).is_err());
+    }
+}